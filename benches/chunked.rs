@@ -0,0 +1,69 @@
+//! Compares `ChunkedStack` with `GenericStack`: execute `cargo bench --bench chunked`.
+//!
+//! We measure pushing and popping `ELEMENTS` elements as well as iterating
+//! over them with the help of `std::time::Instant`.
+
+use ll_stack::{ChunkedStack, GenericStack};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ELEMENTS: u64 = 1_000_000;
+const ROUNDS: u32 = 10;
+
+/// runs `f` `ROUNDS` times and returns the mean duration
+fn measure(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn report(name: &str, generic: Duration, chunked: Duration) {
+    println!(
+        "{name:<8} GenericStack: {generic:>12.3?}  ChunkedStack: {chunked:>12.3?}  speedup: {:.2}",
+        generic.as_secs_f64() / chunked.as_secs_f64()
+    );
+}
+
+fn main() {
+    let push_generic = measure(|| {
+        let mut stack = GenericStack::new();
+        for i in 0..ELEMENTS {
+            stack.push(black_box(i));
+        }
+        black_box(&stack);
+    });
+    let push_chunked = measure(|| {
+        let mut stack: ChunkedStack<u64> = ChunkedStack::new();
+        for i in 0..ELEMENTS {
+            stack.push(black_box(i));
+        }
+        black_box(&stack);
+    });
+    report("push", push_generic, push_chunked);
+
+    let pop_generic = measure(|| {
+        let mut stack: GenericStack<u64> = (0..ELEMENTS).collect();
+        while let Some(v) = stack.pop() {
+            black_box(v);
+        }
+    });
+    let pop_chunked = measure(|| {
+        let mut stack: ChunkedStack<u64> = (0..ELEMENTS).collect();
+        while let Some(v) = stack.pop() {
+            black_box(v);
+        }
+    });
+    report("push+pop", pop_generic, pop_chunked);
+
+    let generic: GenericStack<u64> = (0..ELEMENTS).collect();
+    let chunked: ChunkedStack<u64> = (0..ELEMENTS).collect();
+    let iter_generic = measure(|| {
+        black_box(generic.iter().sum::<u64>());
+    });
+    let iter_chunked = measure(|| {
+        black_box(chunked.iter().sum::<u64>());
+    });
+    report("iter", iter_generic, iter_chunked);
+}
@@ -0,0 +1,172 @@
+//! Compares the stack implementations with the help of criterion: execute
+//! `cargo bench --bench stacks`.
+//!
+//! We measure pushing, popping, and iterating over `ELEMENTS`
+//! elements for `GenericStack`, `Vec`, `VecStack`, and `ChunkedStack` with
+//! elements of 8, 64, and 256 bytes. The reports are written to
+//! `target/criterion`.
+
+use criterion::measurement::WallTime;
+use criterion::{
+    criterion_group, criterion_main, BatchSize, BenchmarkGroup, Criterion, Throughput,
+};
+use ll_stack::{ChunkedStack, GenericStack, VecStack};
+use std::hint::black_box;
+
+const ELEMENTS: u64 = 10_000;
+
+/// an element of `8 * N` bytes
+type Element<const N: usize> = [u64; N];
+
+fn element<const N: usize>(i: u64) -> Element<N> {
+    [i; N]
+}
+
+/// creates a benchmark group for the operation `name` and elements of `N` words
+fn group<'a, const N: usize>(c: &'a mut Criterion, name: &str) -> BenchmarkGroup<'a, WallTime> {
+    let mut group = c.benchmark_group(format!("{name}/{}B", 8 * N));
+    group.throughput(Throughput::Elements(ELEMENTS));
+    group
+}
+
+/// pushes `ELEMENTS` elements on a new stack. The stack is dropped after
+/// the measurement.
+fn push<const N: usize>(c: &mut Criterion) {
+    let mut group = group::<N>(c, "push");
+    group.bench_function("GenericStack", |b| {
+        b.iter_with_large_drop(|| {
+            let mut stack = GenericStack::new();
+            for i in 0..ELEMENTS {
+                stack.push(element::<N>(black_box(i)));
+            }
+            stack
+        })
+    });
+    group.bench_function("Vec", |b| {
+        b.iter_with_large_drop(|| {
+            let mut stack = Vec::new();
+            for i in 0..ELEMENTS {
+                stack.push(element::<N>(black_box(i)));
+            }
+            stack
+        })
+    });
+    group.bench_function("VecStack", |b| {
+        b.iter_with_large_drop(|| {
+            let mut stack = VecStack::new();
+            for i in 0..ELEMENTS {
+                stack.push(element::<N>(black_box(i)));
+            }
+            stack
+        })
+    });
+    group.bench_function("ChunkedStack", |b| {
+        b.iter_with_large_drop(|| {
+            let mut stack: ChunkedStack<Element<N>> = ChunkedStack::new();
+            for i in 0..ELEMENTS {
+                stack.push(element::<N>(black_box(i)));
+            }
+            stack
+        })
+    });
+    group.finish();
+}
+
+/// pops all elements of a stack with `ELEMENTS` elements, which is created
+/// before the measurement.
+fn pop<const N: usize>(c: &mut Criterion) {
+    let mut group = group::<N>(c, "pop");
+    group.bench_function("GenericStack", |b| {
+        b.iter_batched(
+            || (0..ELEMENTS).map(element::<N>).collect::<GenericStack<_>>(),
+            |mut stack| {
+                while let Some(v) = stack.pop() {
+                    black_box(v);
+                }
+                stack
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("Vec", |b| {
+        b.iter_batched(
+            || (0..ELEMENTS).map(element::<N>).collect::<Vec<_>>(),
+            |mut stack| {
+                while let Some(v) = stack.pop() {
+                    black_box(v);
+                }
+                stack
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("VecStack", |b| {
+        b.iter_batched(
+            || VecStack::from((0..ELEMENTS).map(element::<N>).collect::<Vec<_>>()),
+            |mut stack| {
+                while let Some(v) = stack.pop() {
+                    black_box(v);
+                }
+                stack
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("ChunkedStack", |b| {
+        b.iter_batched(
+            || (0..ELEMENTS).map(element::<N>).collect::<ChunkedStack<_>>(),
+            |mut stack| {
+                while let Some(v) = stack.pop() {
+                    black_box(v);
+                }
+                stack
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+/// iterates over the elements of a stack from the top to the bottom
+fn iter<const N: usize>(c: &mut Criterion) {
+    let mut group = group::<N>(c, "iter");
+    let generic: GenericStack<_> = (0..ELEMENTS).map(element::<N>).collect();
+    group.bench_function("GenericStack", |b| {
+        b.iter(|| {
+            for v in generic.iter() {
+                black_box(v);
+            }
+        })
+    });
+    let vec: Vec<_> = (0..ELEMENTS).map(element::<N>).collect();
+    group.bench_function("Vec", |b| {
+        b.iter(|| {
+            for v in vec.iter().rev() {
+                black_box(v);
+            }
+        })
+    });
+    let vec_stack = VecStack::from(vec.clone());
+    group.bench_function("VecStack", |b| {
+        b.iter(|| {
+            for v in vec_stack.iter() {
+                black_box(v);
+            }
+        })
+    });
+    let chunked: ChunkedStack<_> = (0..ELEMENTS).map(element::<N>).collect();
+    group.bench_function("ChunkedStack", |b| {
+        b.iter(|| {
+            for v in chunked.iter() {
+                black_box(v);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches, push::<1>, push::<8>, push::<32>, pop::<1>, pop::<8>, pop::<32>, iter::<1>, iter::<8>,
+    iter::<32>
+);
+criterion_main!(benches);
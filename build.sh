@@ -24,4 +24,5 @@ cargo check --all
 cargo audit
 cargo deny check --config deny.toml
 cargo test
+cargo test --no-default-features
 cargo doc
@@ -0,0 +1,335 @@
+//! A fixed-capacity stack which stores its elements inline.
+
+use crate::display::fmt_head_list;
+#[cfg(feature = "std")]
+use crate::{CapacityStack, Stack, StackExt};
+use core::fmt::{self, Debug, Display};
+use core::iter::Rev;
+use core::mem::MaybeUninit;
+use core::slice;
+
+/// `ArrayStack<T, N>` is an array-based implementation of a stack: it
+/// stores up to `N` elements inline, i.e., it never allocates memory on the
+/// heap. This makes it a drop-in alternative to [`crate::GenericStack`] for
+/// embedded and realtime code. It implements the trait [`Stack`].
+///
+/// Since the capacity is fixed, [`ArrayStack::push`] panics if the stack is
+/// full. Use [`ArrayStack::try_push`] to handle a full stack.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::ArrayStack;
+/// let mut stack: ArrayStack<u32, 4> = ArrayStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.peek(), Some(&2));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.len(), 1);
+/// ```
+pub struct ArrayStack<T, const N: usize> {
+    /// the elements `0..len` are initialized, element `len - 1` is the top
+    elements: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayStack<T, N> {
+    /// Create a new, empty stack which can store up to `N` elements.
+    #[must_use]
+    pub const fn new() -> Self {
+        ArrayStack {
+            elements: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// push a new element on the top element of the stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stack already contains `N` elements.
+    pub fn push(&mut self, element: T) {
+        assert!(
+            self.try_push(element).is_ok(),
+            "ArrayStack is full: capacity is {N}"
+        );
+    }
+
+    /// pushes `element` on the top of the stack if the stack is not full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `element` if the stack is full. The stack is not changed.
+    pub fn try_push(&mut self, element: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(element);
+        }
+        self.elements[self.len].write(element);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the element at index `len` was initialized and is no
+        // longer part of the stack, i.e., it is read exactly once.
+        Some(unsafe { self.elements[self.len].assume_init_read() })
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+
+    /// iterator from the top to the bottom of the stack
+    pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+        self.as_slice().iter().rev()
+    }
+
+    /// mutable iterator from the top to the bottom of the stack
+    pub fn iter_mut(&mut self) -> Rev<slice::IterMut<'_, T>> {
+        self.as_mut_slice().iter_mut().rev()
+    }
+
+    /// borrows the elements as a slice from the bottom to the top, i.e.,
+    /// the last element of the slice is the top of the stack.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the elements `0..len` are initialized
+        unsafe { slice::from_raw_parts(self.elements.as_ptr().cast::<T>(), self.len) }
+    }
+
+    /// borrows the elements as a mutable slice from the bottom to the top.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the elements `0..len` are initialized
+        unsafe { slice::from_raw_parts_mut(self.elements.as_mut_ptr().cast::<T>(), self.len) }
+    }
+
+    /// Returns the number of elements stored on the stack.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximal number of elements the stack can store, i.e., `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the stack contains `N` elements.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Removes all elements from the stack.
+    pub fn clear(&mut self) {
+        let elements: *mut [T] = self.as_mut_slice();
+        // we reset the length first: if dropping an element panics, the
+        // remaining elements are leaked instead of dropped twice
+        self.len = 0;
+        // SAFETY: the elements were initialized and are no longer part of the stack
+        unsafe { elements.drop_in_place() };
+    }
+}
+
+impl<T, const N: usize> Default for ArrayStack<T, N> {
+    fn default() -> Self {
+        ArrayStack::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayStack<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayStack<T, N> {
+    fn clone(&self) -> Self {
+        let mut stack = ArrayStack::new();
+        for element in self.as_slice() {
+            stack.push(element.clone());
+        }
+        stack
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for ArrayStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayStack<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayStack<T, N> {}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayStack<T, N> {
+    type Item = &'a T;
+    type IntoIter = Rev<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayStack<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = Rev<slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// [`ArrayStack`] prints its elements in the same format as
+/// [`crate::GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Display, const N: usize> Display for ArrayStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> Stack<T> for ArrayStack<T, N> {
+    fn new() -> Self {
+        ArrayStack::new()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the stack already contains `N` elements.
+    fn push(&mut self, element: T) {
+        ArrayStack::push(self, element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        ArrayStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        ArrayStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        ArrayStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> StackExt<T> for ArrayStack<T, N> {
+    fn len(&self) -> usize {
+        ArrayStack::len(self)
+    }
+}
+
+/// The capacity of an [`ArrayStack`] is always `N`: it never allocates,
+/// but it cannot grow either.
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> CapacityStack<T> for ArrayStack<T, N> {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is greater than `N`.
+    fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity <= N,
+            "ArrayStack cannot grow: capacity is {N}, requested {capacity}"
+        );
+        ArrayStack::new()
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the stack cannot store `additional` more elements.
+    fn reserve(&mut self, additional: usize) {
+        assert!(
+            additional <= N - self.len,
+            "ArrayStack cannot grow: capacity is {N}, requested {}",
+            self.len.saturating_add(additional)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn basics() {
+        let mut stack: ArrayStack<i32, 3> = ArrayStack::new();
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.capacity(), 3);
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(stack.is_full());
+        assert_eq!(stack.try_push(4), Err(4));
+        assert_eq!(stack.len(), 3);
+
+        assert_eq!(stack.pop(), Some(3));
+        if let Some(value) = stack.peek_mut() {
+            *value = 42;
+        }
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![42, 1]);
+        assert_eq!(stack.as_slice(), &[1, 42]);
+        assert_eq!(stack.to_string(), "head->42->1.");
+        assert_eq!(stack.clone(), stack);
+    }
+
+    #[test]
+    #[should_panic(expected = "ArrayStack is full")]
+    fn push_full() {
+        let mut stack: ArrayStack<i32, 1> = ArrayStack::new();
+        stack.push(1);
+        stack.push(2);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let counter = Rc::new(());
+        let mut stack: ArrayStack<Rc<()>, 4> = ArrayStack::new();
+        for _ in 0..3 {
+            stack.push(Rc::clone(&counter));
+        }
+        drop(stack.pop());
+        assert_eq!(Rc::strong_count(&counter), 3);
+        drop(stack);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stack_trait() {
+        fn fill<S: Stack<u8>>() -> S {
+            let mut stack = S::new();
+            stack.push(1);
+            stack.push(2);
+            stack
+        }
+        let mut stack: ArrayStack<u8, 2> = fill();
+        assert_eq!(Stack::pop(&mut stack), Some(2));
+        assert_eq!(Stack::peek(&stack), Some(&1));
+    }
+}
@@ -0,0 +1,358 @@
+//! A thread-safe stack whose `pop` can be awaited.
+
+use crate::GenericStack;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// `AsyncStack<T>` is a thread-safe stack for async code: the future
+/// returned by [`AsyncStack::pop`] completes as soon as an element is
+/// available, i.e., a task which pops from an empty stack is suspended
+/// until another task or thread pushes an element. This permits LIFO task
+/// scheduling without hand-written wakers.
+///
+/// The stack does not depend on an async runtime: it is a
+/// [`GenericStack<T>`] in a [`Mutex`] together with the [`Waker`]s of the
+/// waiting tasks. Pushing an element wakes up the task which waits the
+/// longest. The lock is never held across an `.await`.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::AsyncStack;
+/// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+/// #     struct Unpark(std::thread::Thread);
+/// #     impl std::task::Wake for Unpark {
+/// #         fn wake(self: std::sync::Arc<Self>) { self.0.unpark() }
+/// #     }
+/// #     let waker = std::sync::Arc::new(Unpark(std::thread::current())).into();
+/// #     let mut context = std::task::Context::from_waker(&waker);
+/// #     let mut future = std::pin::pin!(future);
+/// #     loop {
+/// #         match future.as_mut().poll(&mut context) {
+/// #             std::task::Poll::Ready(output) => return output,
+/// #             std::task::Poll::Pending => std::thread::park(),
+/// #         }
+/// #     }
+/// # }
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let stack = Arc::new(AsyncStack::new());
+/// let producer = {
+///     let stack = Arc::clone(&stack);
+///     thread::spawn(move || stack.push(42))
+/// };
+/// // `block_on` of your executor
+/// assert_eq!(block_on(stack.pop()), 42);
+/// producer.join().unwrap();
+/// ```
+pub struct AsyncStack<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    stack: GenericStack<T>,
+    /// the tasks waiting for an element, the longest waiting task first
+    waiters: VecDeque<(usize, Waker)>,
+    /// identifies the next waiting [`Pop`] future
+    next_id: usize,
+}
+
+impl<T> AsyncStack<T> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        AsyncStack::from(GenericStack::new())
+    }
+
+    /// push a new element on the top of the stack and wakes up one task
+    /// waiting for an element.
+    pub fn push(&self, element: T) {
+        let mut state = self.lock();
+        state.stack.push(element);
+        let waiter = state.waiters.pop_front();
+        // we wake the task after releasing the lock, since it will lock the
+        // stack when it is polled
+        drop(state);
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+
+    /// Returns a future which removes and returns the top element of the
+    /// stack as soon as the stack is not empty.
+    ///
+    /// The element is removed when the future completes, i.e., the top
+    /// element at that time is returned. Dropping the future before it
+    /// completes does not remove an element.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub fn pop(&self) -> Pop<'_, T> {
+        Pop {
+            stack: self,
+            id: None,
+        }
+    }
+
+    /// Removes and returns the top element of the stack if it exists,
+    /// without waiting.
+    #[must_use]
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().stack.pop()
+    }
+
+    /// Returns the number of elements stored on the stack. Other tasks
+    /// might push or pop elements concurrently, i.e., the result might
+    /// already be outdated when this method returns.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().stack.is_empty()
+    }
+
+    /// Consumes the wrapper and returns the protected stack.
+    pub fn into_inner(self) -> GenericStack<T> {
+        self.state
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .stack
+    }
+
+    /// pops the top element, or registers the waker of `cx` under `id` if
+    /// the stack is empty. `id` is assigned when the waker is registered
+    /// for the first time and reset when an element is popped.
+    pub(crate) fn poll_pop(&self, id: &mut Option<usize>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.lock();
+        if let Some(element) = state.stack.pop() {
+            if let Some(id) = id.take() {
+                state.waiters.retain(|(waiter, _)| *waiter != id);
+            }
+            return Poll::Ready(element);
+        }
+
+        let registered =
+            id.and_then(|id| state.waiters.iter_mut().find(|(waiter, _)| *waiter == id));
+        if let Some((_, waker)) = registered {
+            waker.clone_from(cx.waker());
+        } else {
+            // the first poll, or the task was woken, but another task popped
+            // the element in the meantime
+            let id = *id.get_or_insert_with(|| {
+                let id = state.next_id;
+                state.next_id = state.next_id.wrapping_add(1);
+                id
+            });
+            state.waiters.push_back((id, cx.waker().clone()));
+        }
+        Poll::Pending
+    }
+
+    /// removes the waker registered under `id`. If the waker was already
+    /// woken up, the wakeup is passed on to the next waiting task.
+    /// Otherwise, the element might stay on the stack while other tasks
+    /// wait.
+    pub(crate) fn cancel_pop(&self, id: usize) {
+        let mut state = self.lock();
+        let waiting = state.waiters.len();
+        state.waiters.retain(|(waiter, _)| *waiter != id);
+        if state.waiters.len() < waiting || state.stack.is_empty() {
+            return;
+        }
+        let waiter = state.waiters.pop_front();
+        drop(state);
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+
+    /// Like the other thread-safe stacks, we ignore lock poisoning: the
+    /// operations leave the stack and the waiters in a consistent state.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for AsyncStack<T> {
+    fn default() -> Self {
+        AsyncStack::new()
+    }
+}
+
+impl<T> From<GenericStack<T>> for AsyncStack<T> {
+    fn from(stack: GenericStack<T>) -> Self {
+        AsyncStack {
+            state: Mutex::new(State {
+                stack,
+                waiters: VecDeque::new(),
+                next_id: 0,
+            }),
+        }
+    }
+}
+
+impl<T> Debug for AsyncStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("AsyncStack")
+            .field("len", &state.stack.len())
+            .field("waiters", &state.waiters.len())
+            .finish()
+    }
+}
+
+/// The future returned by [`AsyncStack::pop`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Pop<'a, T> {
+    stack: &'a AsyncStack<T>,
+    /// identifies the waker of this future once it was registered
+    id: Option<usize>,
+}
+
+impl<T> Future for Pop<'_, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = self.get_mut();
+        this.stack.poll_pop(&mut this.id, cx)
+    }
+}
+
+/// A future which was woken up, but is dropped before it popped an element,
+/// passes the wakeup on to the next waiting task.
+impl<T> Drop for Pop<'_, T> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.stack.cancel_pop(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use static_assertions::assert_impl_all;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread;
+
+    assert_impl_all!(AsyncStack<i32>: Send, Sync);
+    assert_impl_all!(Pop<'static, i32>: Send, Sync);
+
+    /// counts how often the task was woken up
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn poll<T>(future: &mut Pop<'_, T>, counter: &Arc<Counter>) -> Poll<T> {
+        let waker = Waker::from(Arc::clone(counter));
+        Pin::new(future).poll(&mut Context::from_waker(&waker))
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unpark(thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn pop_waits_for_push() {
+        let stack = AsyncStack::new();
+        let counter = Arc::new(Counter::default());
+        let mut pop = stack.pop();
+        assert_eq!(poll(&mut pop, &counter), Poll::Pending);
+        assert_eq!(poll(&mut pop, &counter), Poll::Pending);
+        // polling twice registers the task once
+        assert_eq!(format!("{stack:?}"), "AsyncStack { len: 0, waiters: 1 }");
+
+        stack.push(1);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert_eq!(poll(&mut pop, &counter), Poll::Ready(1));
+        drop(pop);
+        assert_eq!(format!("{stack:?}"), "AsyncStack { len: 0, waiters: 0 }");
+
+        stack.push(2);
+        assert_eq!(stack.try_pop(), Some(2));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn dropped_future_passes_wakeup_on() {
+        let stack = AsyncStack::new();
+        let first = Arc::new(Counter::default());
+        let second = Arc::new(Counter::default());
+        let mut pop1 = stack.pop();
+        let mut pop2 = stack.pop();
+        assert_eq!(poll(&mut pop1, &first), Poll::Pending);
+        assert_eq!(poll(&mut pop2, &second), Poll::Pending);
+
+        // the longest waiting task is woken up, but does not pop
+        stack.push(1);
+        assert_eq!(first.0.load(Ordering::SeqCst), 1);
+        assert_eq!(second.0.load(Ordering::SeqCst), 0);
+        drop(pop1);
+        assert_eq!(second.0.load(Ordering::SeqCst), 1);
+        assert_eq!(poll(&mut pop2, &second), Poll::Ready(1));
+    }
+
+    #[test]
+    fn threads() {
+        const ELEMENTS: usize = 100;
+        let stack = Arc::new(AsyncStack::new());
+        let consumers: Vec<_> = (0..4)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    // `None` marks the end of the elements
+                    while let Some(element) = block_on(stack.pop()) {
+                        popped.push(element);
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        for element in 0..ELEMENTS {
+            stack.push(Some(element));
+        }
+        for _ in 0..consumers.len() {
+            while !stack.is_empty() {
+                thread::yield_now();
+            }
+            stack.push(None);
+        }
+
+        let mut all: Vec<_> = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..ELEMENTS).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,143 @@
+//! A stack with a fixed maximal number of elements.
+
+use crate::{GenericStack, Iter};
+
+/// `BoundedStack<T>` is a [`GenericStack<T>`] with a capacity: pushing an
+/// element on a full stack fails and returns the element to the caller.
+/// This provides back-pressure to producers that must not grow the stack
+/// without bounds.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::BoundedStack;
+/// let mut stack = BoundedStack::new(2);
+/// assert_eq!(stack.try_push(1), Ok(()));
+/// assert_eq!(stack.try_push(2), Ok(()));
+/// assert_eq!(stack.try_push(3), Err(3));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.try_push(3), Ok(()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundedStack<T> {
+    stack: GenericStack<T>,
+    capacity: usize,
+}
+
+impl<T> BoundedStack<T> {
+    /// Create a new, empty stack which can store at most `capacity` elements.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        BoundedStack {
+            stack: GenericStack::new(),
+            capacity,
+        }
+    }
+
+    /// pushes `element` on the top of the stack if the stack is not full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `element` if the stack is full. The stack is not changed.
+    pub fn try_push(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(element);
+        }
+        self.stack.push(element);
+        Ok(())
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.stack.peek()
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.stack.peek_mut()
+    }
+
+    /// iterator from the top to the bottom of the stack
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.stack.iter()
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns the maximal number of elements the stack can store.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the next `try_push` would fail.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.stack.len() >= self.capacity
+    }
+
+    /// Consumes the bounded stack and returns the underlying stack.
+    #[must_use]
+    pub fn into_inner(self) -> GenericStack<T> {
+        self.stack
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedStack<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_push() {
+        let mut stack = BoundedStack::new(3);
+        assert_eq!(stack.capacity(), 3);
+        assert!(stack.is_empty());
+        for i in 0..3 {
+            assert_eq!(stack.try_push(i), Ok(()));
+        }
+        assert!(stack.is_full());
+        assert_eq!(stack.try_push(3), Err(3));
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.peek(), Some(&2));
+
+        assert_eq!(stack.pop(), Some(2));
+        assert!(!stack.is_full());
+        assert_eq!(stack.try_push(4), Ok(()));
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![4, 1, 0]);
+        assert_eq!(Vec::from(stack.into_inner()), vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut stack = BoundedStack::new(0);
+        assert!(stack.is_full());
+        assert_eq!(stack.try_push("a"), Err("a"));
+        assert_eq!(stack.pop(), None);
+    }
+}
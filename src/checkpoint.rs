@@ -0,0 +1,109 @@
+//! Checkpoints of a [`GenericStack<T>`] for backtracking: a checkpoint
+//! remembers the depth of the stack, and rolling back to the checkpoint
+//! pops all elements that were pushed afterwards.
+
+use crate::{Allocator, GenericStack};
+
+/// Identifies a checkpoint returned by [`GenericStack::checkpoint`]. A
+/// checkpoint is just the depth of the stack at the time it was taken,
+/// i.e., checkpoints are ordered: later checkpoints of a growing stack are
+/// greater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CheckpointId(usize);
+
+impl CheckpointId {
+    /// Returns the depth of the stack at the checkpoint.
+    #[must_use]
+    pub fn depth(self) -> usize {
+        self.0
+    }
+}
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Returns a checkpoint of the current depth of the stack. Pass it to
+    /// [`GenericStack::rollback_to`] to pop all elements pushed after the
+    /// checkpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2]);
+    /// let checkpoint = stack.checkpoint();
+    /// stack.push(3);
+    /// stack.push(4);
+    /// stack.rollback_to(checkpoint);
+    /// assert_eq!(Vec::from(stack), vec![1, 2]);
+    /// ```
+    pub fn checkpoint(&self) -> CheckpointId {
+        CheckpointId(self.len)
+    }
+
+    /// Pops and drops all elements above the depth of the `checkpoint`.
+    /// The nodes are kept in the node pool, i.e., pushing after a rollback
+    /// does not allocate. If the stack has been popped below the checkpoint
+    /// in the meantime, the stack is not changed.
+    ///
+    /// Since we only remember the depth, this does not detect elements that
+    /// were popped and pushed again after the checkpoint, i.e., rolling back
+    /// restores the depth but not necessarily the elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// stack.push('a');
+    /// let first = stack.checkpoint();
+    /// stack.push('b');
+    /// let second = stack.checkpoint();
+    /// stack.push('c');
+    ///
+    /// stack.rollback_to(second);
+    /// assert_eq!(stack.peek(), Some(&'b'));
+    /// stack.rollback_to(first);
+    /// assert_eq!(stack.peek(), Some(&'a'));
+    /// // a rollback to a later checkpoint does nothing
+    /// stack.rollback_to(second);
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    pub fn rollback_to(&mut self, checkpoint: CheckpointId) {
+        while self.len > checkpoint.0 {
+            self.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rollback() {
+        let mut stack = GenericStack::new();
+        let empty = stack.checkpoint();
+        assert_eq!(empty.depth(), 0);
+        stack.push_all(1..=3);
+        let checkpoint = stack.checkpoint();
+        assert_eq!(checkpoint.depth(), 3);
+        assert!(empty < checkpoint);
+
+        // backtracking: try different continuations from the same state
+        for attempt in 0..3 {
+            stack.push_all(10 * attempt..10 * attempt + 5);
+            assert_eq!(stack.len(), 8);
+            stack.rollback_to(checkpoint);
+            assert_eq!(stack.len(), 3);
+            assert_eq!(stack.peek(), Some(&3));
+        }
+        // the popped nodes are reused
+        assert_eq!(stack.capacity(), 8);
+
+        stack.rollback_to(checkpoint);
+        assert_eq!(stack.len(), 3);
+        stack.rollback_to(empty);
+        assert!(stack.is_empty());
+        stack.rollback_to(checkpoint);
+        assert!(stack.is_empty());
+    }
+}
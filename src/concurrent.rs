@@ -0,0 +1,952 @@
+//! Stacks which can be shared between threads.
+//!
+//! [`TreiberStack<T>`] is a lock-free stack: threads push and pop elements
+//! with the help of compare-and-swap (CAS) loops on an [`AtomicPtr`] instead
+//! of acquiring a lock.
+//!
+//! `SyncStack<T>` (requires feature `std`) protects a [`GenericStack<T>`]
+//! with a mutex.
+//!
+//! `BlockingStack<T>` (requires feature `std`) adds a condition variable,
+//! i.e., threads can wait until an element is pushed. This turns the stack
+//! into a LIFO work queue.
+//!
+//! # Testing with loom
+//!
+//! With `--cfg loom`, the stacks use the atomics and the mutex of crate
+//! [loom](https://docs.rs/loom), and the tests of this module explore all
+//! interleavings of concurrent pushes and pops:
+//!
+//! `RUSTFLAGS="--cfg loom" cargo test --release --lib concurrent`
+
+#[cfg(feature = "std")]
+use crate::GenericStack;
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+use core::ptr;
+#[cfg(not(loom))]
+use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+#[cfg(loom)]
+use loom::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+#[cfg(all(feature = "std", loom))]
+use loom::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(feature = "std")]
+use std::sync::PoisonError;
+#[cfg(all(feature = "std", not(loom)))]
+use std::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// `TreiberStack<T>` is a lock-free stack (R. K. Treiber, 1986): the stack
+/// is a singly-linked list whose head is an [`AtomicPtr`]. To push an
+/// element, we link a new node to the current head and try to replace the
+/// head by the new node with a CAS. To pop an element, we try to replace
+/// the head by its successor with a CAS. If another thread changed the
+/// head in the meantime, the CAS fails and we retry.
+///
+/// # Memory Reclamation
+///
+/// A thread that pops a node cannot free it immediately: another thread
+/// might have loaded the same head and still read its next pointer. Hence,
+/// we use epoch-based reclamation (K. Fraser, 2004):
+///
+/// - The stack counts a global epoch. A thread that pops an element is
+///   pinned, i.e., it announces the epoch it observed in a participant
+///   record of the stack until the pop returns.
+/// - A popped node is retired together with the current global epoch.
+/// - The global epoch advances if all pinned threads observed it. Hence,
+///   once the epoch advanced twice after a node was retired, no thread can
+///   still read the node, and it is freed.
+///
+/// Pushing and popping stay lock-free, and the number of retired nodes
+/// that are not freed yet is bounded unless a thread stalls inside of a
+/// pop. A node is not freed while a thread that might have loaded it is
+/// pinned, which also prevents the ABA problem: the address of a node
+/// cannot be reused while a thread compares the head with it.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::concurrent::TreiberStack;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let stack = Arc::new(TreiberStack::new());
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let stack = Arc::clone(&stack);
+///         thread::spawn(move || stack.push(i))
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// let mut sum = 0;
+/// while let Some(v) = stack.pop() {
+///     sum += v;
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    /// the retired nodes which are not freed yet
+    retired: AtomicPtr<Node<T>>,
+    /// the global epoch
+    epoch: AtomicUsize,
+    /// the participant records, which are only freed with the stack
+    participants: AtomicPtr<Participant>,
+}
+
+struct Node<T> {
+    /// the element is moved out by the thread that pops the node
+    element: ManuallyDrop<T>,
+    /// the next pointer is written before the node is published and is
+    /// read-only afterwards
+    next: *mut Node<T>,
+    /// links the nodes on the list of retired nodes. We use a separate
+    /// field since other threads might still read `next` of a retired node.
+    next_retired: *mut Node<T>,
+    /// the global epoch when the node was retired
+    retired_epoch: usize,
+}
+
+/// announces the epoch observed by a thread while it pops an element
+struct Participant {
+    /// `true` while a thread uses the record
+    in_use: AtomicBool,
+    /// `epoch << 1 | 1` while the thread is pinned, and `0` otherwise
+    state: AtomicUsize,
+    /// the records are pushed on a list, but never removed
+    next: *mut Participant,
+}
+
+// SAFETY: the elements are moved between threads, but never shared: each
+// element is moved out by exactly one thread, i.e., the thread whose CAS
+// removed its node from the stack.
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    /// Create a new, empty lock-free stack.
+    #[cfg(not(loom))]
+    #[must_use]
+    pub const fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            epoch: AtomicUsize::new(0),
+            participants: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Create a new, empty lock-free stack. The atomics of loom cannot be
+    /// created in a `const fn`.
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            epoch: AtomicUsize::new(0),
+            participants: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// push a new element on the top of the stack.
+    pub fn push(&self, element: T) {
+        let node = Box::into_raw(Box::new(Node {
+            element: ManuallyDrop::new(element),
+            next: ptr::null_mut(),
+            next_retired: ptr::null_mut(),
+            retired_epoch: 0,
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: the node is not yet published, i.e., we own it
+            unsafe { (*node).next = head };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&self) -> Option<T> {
+        let participant = self.pin();
+        let mut head = self.head.load(Ordering::Acquire);
+        let element = loop {
+            if head.is_null() {
+                break None;
+            }
+            // SAFETY: we are pinned, i.e., `head` is not freed even if
+            // another thread popped it in the meantime.
+            let next = unsafe { (*head).next };
+            match self
+                .head
+                .compare_exchange_weak(head, next, Ordering::SeqCst, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    // SAFETY: our CAS removed the node, i.e., no other thread
+                    // can move the element out of this node.
+                    let element = unsafe { ManuallyDrop::take(&mut (*head).element) };
+                    self.retire(head);
+                    break Some(element);
+                }
+                Err(current) => head = current,
+            }
+        };
+        Self::unpin(participant);
+
+        if element.is_some() {
+            self.try_advance();
+            self.collect();
+        }
+        element
+    }
+
+    /// Returns `true` if the stack does not contain any elements. Other
+    /// threads might push or pop elements concurrently, i.e., the result
+    /// might already be outdated when this method returns.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// announces the current global epoch in an unused participant
+    /// record, which is allocated if all records are in use.
+    fn pin(&self) -> &Participant {
+        let participant = self.acquire_participant();
+        let mut epoch = self.epoch.load(Ordering::SeqCst);
+        loop {
+            participant.state.store(epoch << 1 | 1, Ordering::SeqCst);
+            fence(Ordering::SeqCst);
+            // the epoch might have advanced before our announcement was
+            // visible, i.e., without taking us into account
+            let current = self.epoch.load(Ordering::SeqCst);
+            if current == epoch {
+                return participant;
+            }
+            epoch = current;
+        }
+    }
+
+    fn unpin(participant: &Participant) {
+        participant.state.store(0, Ordering::SeqCst);
+        participant.in_use.store(false, Ordering::Release);
+    }
+
+    fn acquire_participant(&self) -> &Participant {
+        let mut record = self.participants.load(Ordering::Acquire);
+        while !record.is_null() {
+            // SAFETY: participant records are only freed with the stack
+            let participant = unsafe { &*record };
+            if participant
+                .in_use
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return participant;
+            }
+            record = participant.next;
+        }
+
+        let record = Box::into_raw(Box::new(Participant {
+            in_use: AtomicBool::new(true),
+            state: AtomicUsize::new(0),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.participants.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: the record is not yet published, i.e., we own it
+            unsafe { (*record).next = head };
+            match self.participants.compare_exchange_weak(
+                head,
+                record,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                // SAFETY: participant records are only freed with the stack
+                Ok(_) => return unsafe { &*record },
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// advances the global epoch if all pinned threads observed it
+    fn try_advance(&self) {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let pinned = epoch << 1 | 1;
+        let mut record = self.participants.load(Ordering::Acquire);
+        while !record.is_null() {
+            // SAFETY: participant records are only freed with the stack
+            let participant = unsafe { &*record };
+            let state = participant.state.load(Ordering::SeqCst);
+            if state != 0 && state != pinned {
+                return;
+            }
+            record = participant.next;
+        }
+        // another thread might have advanced the epoch in the meantime
+        let _ = self.epoch.compare_exchange(
+            epoch,
+            epoch.wrapping_add(1),
+            Ordering::SeqCst,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// frees the retired nodes which cannot be read by any thread anymore
+    /// and puts the others back on the list of retired nodes
+    fn collect(&self) {
+        let mut node = self.retired.swap(ptr::null_mut(), Ordering::Acquire);
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        let mut kept: *mut Node<T> = ptr::null_mut();
+        let mut last_kept: *mut Node<T> = ptr::null_mut();
+        while !node.is_null() {
+            // SAFETY: the swap made us the only owner of the retired nodes
+            let next = unsafe { (*node).next_retired };
+            if epoch.wrapping_sub(unsafe { (*node).retired_epoch }) >= 2 {
+                // SAFETY: every thread which might have loaded the node was
+                // unpinned before the epoch advanced twice; the element was
+                // moved out when the node was popped
+                drop(unsafe { Box::from_raw(node) });
+            } else {
+                // SAFETY: see above
+                unsafe { (*node).next_retired = kept };
+                if kept.is_null() {
+                    last_kept = node;
+                }
+                kept = node;
+            }
+            node = next;
+        }
+        if !kept.is_null() {
+            // SAFETY: the kept nodes are still owned by us
+            unsafe { self.push_retired(kept, last_kept) };
+        }
+    }
+
+    /// pushes a popped node on the list of retired nodes
+    fn retire(&self, node: *mut Node<T>) {
+        // SAFETY: only the thread that popped the node writes this field
+        unsafe { (*node).retired_epoch = self.epoch.load(Ordering::SeqCst) };
+        // SAFETY: the node is unlinked and owned by us
+        unsafe { self.push_retired(node, node) };
+    }
+
+    /// pushes the chain of retired nodes from `first` to `last` on the
+    /// list of retired nodes.
+    ///
+    /// # Safety
+    ///
+    /// The nodes must be owned by the calling thread.
+    unsafe fn push_retired(&self, first: *mut Node<T>, last: *mut Node<T>) {
+        let mut retired = self.retired.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: only the owner of the chain writes this field
+            unsafe { (*last).next_retired = retired };
+            match self.retired.compare_exchange_weak(
+                retired,
+                first,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(current) => retired = current,
+            }
+        }
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        TreiberStack::new()
+    }
+}
+
+/// Dropping the stack drops the remaining elements and frees all nodes,
+/// including the retired ones, and the participant records. We have
+/// exclusive access, i.e., no other thread can still read any node. Hence, relaxed loads suffice (the
+/// atomics of loom do not provide `get_mut`).
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut node = self.head.load(Ordering::Relaxed);
+        while !node.is_null() {
+            // SAFETY: we own all nodes that are still on the stack
+            let mut boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next;
+            // SAFETY: the element of a node on the stack was not moved out
+            unsafe { ManuallyDrop::drop(&mut boxed.element) };
+        }
+
+        let mut node = self.retired.load(Ordering::Relaxed);
+        while !node.is_null() {
+            // SAFETY: we own all retired nodes; their elements were moved out
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next_retired;
+        }
+
+        let mut record = self.participants.load(Ordering::Relaxed);
+        while !record.is_null() {
+            // SAFETY: we own all participant records
+            let boxed = unsafe { Box::from_raw(record) };
+            record = boxed.next;
+        }
+    }
+}
+
+/// `SyncStack<T>` is a thread-safe stack: it wraps a [`GenericStack<T>`]
+/// in a [`Mutex`], i.e., each operation locks the stack. Share it between
+/// threads with the help of an `Arc`.
+///
+/// A panic of another thread while holding the lock does not make the
+/// stack unusable: the operations of [`GenericStack<T>`] leave the stack in
+/// a consistent state, i.e., we ignore lock poisoning.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::concurrent::SyncStack;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let stack = Arc::new(SyncStack::new());
+/// let producer = {
+///     let stack = Arc::clone(&stack);
+///     thread::spawn(move || stack.push(42))
+/// };
+/// producer.join().unwrap();
+/// assert_eq!(stack.peek_cloned(), Some(42));
+/// assert_eq!(stack.pop(), Some(42));
+/// ```
+#[cfg(feature = "std")]
+pub struct SyncStack<T> {
+    stack: Mutex<GenericStack<T>>,
+}
+
+#[cfg(feature = "std")]
+impl<T> SyncStack<T> {
+    /// Create a new, empty thread-safe stack.
+    #[must_use]
+    pub fn new() -> Self {
+        SyncStack {
+            stack: Mutex::new(GenericStack::new()),
+        }
+    }
+
+    /// push a new element on the top of the stack.
+    pub fn push(&self, element: T) {
+        self.lock().push(element);
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&self) -> Option<T> {
+        self.lock().pop()
+    }
+
+    /// Returns a clone of the top element of the stack if it exists. We
+    /// cannot return a reference since the lock is released on return.
+    pub fn peek_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.lock().peek().cloned()
+    }
+
+    /// Returns the number of elements stored on the stack. Other threads
+    /// might push or pop elements concurrently, i.e., the result might
+    /// already be outdated when this method returns.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Consumes the wrapper and returns the protected stack.
+    pub fn into_inner(self) -> GenericStack<T> {
+        self.stack
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GenericStack<T>> {
+        self.stack.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Default for SyncStack<T> {
+    fn default() -> Self {
+        SyncStack::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<GenericStack<T>> for SyncStack<T> {
+    fn from(stack: GenericStack<T>) -> Self {
+        SyncStack {
+            stack: Mutex::new(stack),
+        }
+    }
+}
+
+/// `BlockingStack<T>` is a thread-safe stack whose consumers can wait for
+/// elements: [`BlockingStack::pop_wait`] blocks until an element is
+/// available and [`BlockingStack::pop_timeout`] gives up after a timeout.
+/// The stack is a [`GenericStack<T>`] in a [`Mutex`], and pushing an
+/// element wakes up one waiting thread with the help of a [`Condvar`].
+///
+/// This makes the stack a LIFO work queue, e.g., for a thread pool which
+/// should process the most recent tasks first. Like [`SyncStack`], we
+/// ignore lock poisoning.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::concurrent::BlockingStack;
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let stack = Arc::new(BlockingStack::new());
+/// let worker = {
+///     let stack = Arc::clone(&stack);
+///     thread::spawn(move || stack.pop_wait())
+/// };
+/// stack.push(42);
+/// assert_eq!(worker.join().unwrap(), 42);
+/// assert_eq!(stack.pop_timeout(Duration::from_millis(10)), None);
+/// ```
+#[cfg(feature = "std")]
+pub struct BlockingStack<T> {
+    stack: Mutex<GenericStack<T>>,
+    /// notified whenever an element is pushed
+    available: Condvar,
+}
+
+#[cfg(feature = "std")]
+impl<T> BlockingStack<T> {
+    /// Create a new, empty blocking stack.
+    #[must_use]
+    pub fn new() -> Self {
+        BlockingStack::from(GenericStack::new())
+    }
+
+    /// push a new element on the top of the stack and wakes up one thread
+    /// waiting for an element.
+    pub fn push(&self, element: T) {
+        self.lock().push(element);
+        self.available.notify_one();
+    }
+
+    /// Removes and returns the top element of the stack if it exists,
+    /// without waiting.
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().pop()
+    }
+
+    /// Removes and returns the top element of the stack. If the stack is
+    /// empty, the calling thread blocks until an element is pushed.
+    pub fn pop_wait(&self) -> T {
+        let mut stack = self.lock();
+        loop {
+            if let Some(element) = stack.pop() {
+                return element;
+            }
+            stack = self
+                .available
+                .wait(stack)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Removes and returns the top element of the stack. If the stack is
+    /// empty, the calling thread blocks until an element is pushed or
+    /// `timeout` has elapsed; `None` is returned on timeout.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut stack = self.lock();
+        loop {
+            if let Some(element) = stack.pop() {
+                return Some(element);
+            }
+            // a timeout which overflows `Instant` never expires
+            let remaining = match deadline {
+                Some(deadline) => deadline.checked_duration_since(Instant::now())?,
+                None => timeout,
+            };
+            // spurious wakeups and elements popped by other threads are
+            // handled by the loop
+            stack = self
+                .available
+                .wait_timeout(stack, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Returns the number of elements stored on the stack. Other threads
+    /// might push or pop elements concurrently, i.e., the result might
+    /// already be outdated when this method returns.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Consumes the wrapper and returns the protected stack.
+    pub fn into_inner(self) -> GenericStack<T> {
+        self.stack
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GenericStack<T>> {
+        self.stack.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Default for BlockingStack<T> {
+    fn default() -> Self {
+        BlockingStack::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<GenericStack<T>> for BlockingStack<T> {
+    fn from(stack: GenericStack<T>) -> Self {
+        BlockingStack {
+            stack: Mutex::new(stack),
+            available: Condvar::new(),
+        }
+    }
+}
+
+// the tests use the threads of `std`, which cannot be combined with loom
+#[cfg(all(test, not(loom)))]
+mod test {
+    use super::*;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+
+    // the concurrent stacks can be shared if the elements can be sent
+    assert_impl_all!(TreiberStack<i32>: Send, Sync);
+    assert_impl_all!(TreiberStack<Cell<i32>>: Send, Sync);
+    assert_not_impl_any!(TreiberStack<Rc<i32>>: Send, Sync);
+    #[cfg(feature = "std")]
+    assert_impl_all!(SyncStack<i32>: Send, Sync);
+    #[cfg(feature = "std")]
+    assert_impl_all!(SyncStack<Cell<i32>>: Send, Sync);
+    #[cfg(feature = "std")]
+    assert_not_impl_any!(SyncStack<Rc<i32>>: Send, Sync);
+    #[cfg(feature = "std")]
+    assert_impl_all!(BlockingStack<i32>: Send, Sync);
+    #[cfg(feature = "std")]
+    assert_not_impl_any!(BlockingStack<Rc<i32>>: Send, Sync);
+
+    #[test]
+    fn basics() {
+        let stack = TreiberStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+
+        stack.push(4);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn drop_remaining_elements() {
+        let counter = Arc::new(());
+        let stack = TreiberStack::new();
+        for _ in 0..10 {
+            stack.push(Arc::clone(&counter));
+        }
+        drop(stack.pop());
+        assert_eq!(Arc::strong_count(&counter), 10);
+        drop(stack);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn reclaim_retired_nodes() {
+        let stack = TreiberStack::new();
+        for i in 0..100 {
+            stack.push(i);
+            assert_eq!(stack.pop(), Some(i));
+        }
+
+        // without concurrent pops, a node is freed two pops later
+        let mut retired = 0;
+        let mut node = stack.retired.load(Ordering::Relaxed);
+        while !node.is_null() {
+            retired += 1;
+            node = unsafe { (*node).next_retired };
+        }
+        assert!(retired <= 2, "{retired} retired nodes");
+        assert!(stack.epoch.load(Ordering::Relaxed) >= 100);
+    }
+
+    #[test]
+    fn concurrent_push_pop() {
+        const THREADS: usize = 8;
+        // Miri is too slow for many operations
+        const PER_THREAD: usize = if cfg!(miri) { 50 } else { 1000 };
+
+        let stack = Arc::new(TreiberStack::new());
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 0 {
+                            popped.extend(stack.pop());
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut all: Vec<usize> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        while let Some(v) = stack.pop() {
+            all.push(v);
+        }
+
+        // every pushed element is popped exactly once
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS * PER_THREAD).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_stack() {
+        let stack = SyncStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.peek_cloned(), None::<i32>);
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.peek_cloned(), Some(2));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(Vec::from(stack.into_inner()), vec![1]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_stack_threads() {
+        let stack = Arc::new(SyncStack::from(GenericStack::new()));
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        stack.push(t * 100 + i);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(stack.len(), 400);
+
+        let stack = Arc::try_unwrap(stack).ok().unwrap();
+        let mut all = Vec::from(stack.into_inner());
+        all.sort_unstable();
+        assert_eq!(all, (0..400).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn blocking_stack() {
+        let stack = BlockingStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.try_pop(), None::<i32>);
+        assert_eq!(stack.pop_timeout(Duration::from_millis(1)), None);
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop_wait(), 2);
+        assert_eq!(stack.pop_timeout(Duration::MAX), Some(1));
+        stack.push(3);
+        assert_eq!(Vec::from(stack.into_inner()), vec![3]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn blocking_stack_workers() {
+        const TASKS: usize = 100;
+        let stack = Arc::new(BlockingStack::new());
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut done = Vec::new();
+                    // `None` marks the end of the work
+                    while let Some(task) = stack.pop_wait() {
+                        done.push(task);
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for task in 0..TASKS {
+            stack.push(Some(task));
+        }
+        for _ in 0..workers.len() {
+            // the markers are pushed after all tasks were popped
+            while !stack.is_empty() {
+                thread::yield_now();
+            }
+            stack.push(None);
+        }
+
+        let mut all: Vec<_> = workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..TASKS).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_stack_poisoned() {
+        let stack = Arc::new(SyncStack::new());
+        stack.push(1);
+        let other = Arc::clone(&stack);
+        let result = thread::spawn(move || {
+            let _guard = other.lock();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert_eq!(stack.pop(), Some(1));
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_test {
+    use super::*;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn concurrent_push() {
+        loom::model(|| {
+            let stack = Arc::new(TreiberStack::new());
+            let handles: Vec<_> = (0..2)
+                .map(|i| {
+                    let stack = Arc::clone(&stack);
+                    thread::spawn(move || stack.push(i))
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            let mut all = vec![stack.pop().unwrap(), stack.pop().unwrap()];
+            all.sort_unstable();
+            assert_eq!(all, vec![0, 1]);
+            assert_eq!(stack.pop(), None);
+        });
+    }
+
+    #[test]
+    fn concurrent_pop() {
+        loom::model(|| {
+            let stack = Arc::new(TreiberStack::new());
+            stack.push(1);
+            stack.push(2);
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let stack = Arc::clone(&stack);
+                    thread::spawn(move || stack.pop())
+                })
+                .collect();
+
+            // each element is popped by exactly one thread
+            let mut all: Vec<_> = handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap().unwrap())
+                .collect();
+            all.sort_unstable();
+            assert_eq!(all, vec![1, 2]);
+            assert!(stack.is_empty());
+        });
+    }
+
+    #[test]
+    fn concurrent_push_pop() {
+        loom::model(|| {
+            let stack = Arc::new(TreiberStack::new());
+            stack.push(1);
+            let pusher = {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || stack.push(2))
+            };
+            let popper = {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || stack.pop())
+            };
+            pusher.join().unwrap();
+            let popped = popper.join().unwrap().unwrap();
+            let remaining = stack.pop().unwrap();
+
+            // the popper sees either element, but never loses one
+            let mut all = vec![popped, remaining];
+            all.sort_unstable();
+            assert_eq!(all, vec![1, 2]);
+            assert_eq!(stack.pop(), None);
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_stack() {
+        loom::model(|| {
+            let stack = Arc::new(SyncStack::new());
+            let pusher = {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || stack.push(1))
+            };
+            let popped = stack.pop();
+            pusher.join().unwrap();
+
+            // the pop happens either before or after the push
+            match popped {
+                Some(v) => assert_eq!(v, 1),
+                None => assert_eq!(stack.pop(), Some(1)),
+            }
+            assert!(stack.is_empty());
+        });
+    }
+}
@@ -0,0 +1,493 @@
+//! Cursors over a [`GenericStack<T>`]: in contrast to an iterator, a cursor
+//! can move in both directions and be rewound.
+//!
+//! Like the cursors of `std::collections::LinkedList`, a cursor either
+//! points to an element of the stack or to a "ghost" position, which sits
+//! between the bottom and the top of the stack. Moving down from the bottom
+//! element (or up from the top element) moves the cursor to the ghost
+//! position, moving down from the ghost position moves the cursor to the top
+//! element, and moving up from the ghost position moves it to the bottom.
+//!
+//! A [`CursorMut`] can also modify the stack at its position. Since the
+//! next link of a node points down the stack, "after" the current element
+//! means below it and "before" the current element means above it.
+
+use crate::{Allocator, GenericStack, Global, Link};
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Returns a cursor pointing to the top element of the stack, or to the
+    /// ghost position if the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=3).collect();
+    /// let mut cursor = stack.cursor();
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// cursor.move_down();
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// assert_eq!(cursor.depth(), Some(1));
+    /// cursor.rewind();
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// ```
+    pub fn cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            current: self.head,
+            depth: 0,
+            stack: self,
+        }
+    }
+
+    /// Returns a cursor pointing to the top element of the stack, or to the
+    /// ghost position if the stack is empty. The cursor can insert and
+    /// remove elements at its position in O(1).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 4]);
+    /// let mut cursor = stack.cursor_mut();
+    /// cursor.move_down();
+    /// assert_eq!(cursor.remove_current(), Some(2));
+    /// cursor.insert_after(0);
+    /// cursor.insert_before(3);
+    /// assert_eq!(Vec::from(stack), vec![0, 1, 3, 4]);
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            current: self.head,
+            depth: 0,
+            stack: self,
+        }
+    }
+}
+
+/// Read-only cursor returned by [`GenericStack::cursor`].
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    /// the node the cursor points to, `None` at the ghost position
+    current: Link<T>,
+    /// the number of elements above the current element. The depth is
+    /// equal to the length of the stack at the ghost position.
+    depth: usize,
+    stack: &'a GenericStack<T, A>,
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    /// borrows the element the cursor points to. This will return `None`
+    /// at the ghost position.
+    #[must_use]
+    pub fn current(&self) -> Option<&'a T> {
+        // SAFETY: the node is owned by the borrowed stack which outlives `'a`
+        self.current
+            .map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// Returns the depth of the current element, i.e., the number of
+    /// elements above it: the top element has depth 0. This will return
+    /// `None` at the ghost position.
+    #[must_use]
+    pub fn depth(&self) -> Option<usize> {
+        self.current.map(|_| self.depth)
+    }
+
+    /// Moves the cursor to the element below the current element. At the
+    /// bottom element, the cursor moves to the ghost position, and from the
+    /// ghost position, it moves to the top element.
+    pub fn move_down(&mut self) {
+        if let Some(node) = self.current {
+            // SAFETY: the node is owned by the borrowed stack
+            self.current = unsafe { (*node.as_ptr()).next };
+            self.depth += 1;
+        } else {
+            self.current = self.stack.head;
+            self.depth = 0;
+        }
+    }
+
+    /// Moves the cursor to the element above the current element. At the
+    /// top element, the cursor moves to the ghost position, and from the
+    /// ghost position, it moves to the bottom element.
+    pub fn move_up(&mut self) {
+        if let Some(node) = self.current {
+            // SAFETY: the node is owned by the borrowed stack
+            self.current = unsafe { (*node.as_ptr()).prev };
+            self.depth = match self.current {
+                Some(_) => self.depth - 1,
+                None => self.stack.len,
+            };
+        } else {
+            self.current = self.stack.tail;
+            self.depth = self.stack.len.saturating_sub(1);
+        }
+    }
+
+    /// Moves the cursor back to the top element (or to the ghost position
+    /// if the stack is empty).
+    pub fn rewind(&mut self) {
+        self.current = self.stack.head;
+        self.depth = 0;
+    }
+
+    /// borrows the element below the current element without moving the
+    /// cursor. At the ghost position, this is the top element.
+    #[must_use]
+    pub fn peek_below(&self) -> Option<&'a T> {
+        let below = match self.current {
+            // SAFETY: the node is owned by the borrowed stack
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.stack.head,
+        };
+        // SAFETY: the node is owned by the borrowed stack which outlives `'a`
+        below.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// borrows the element above the current element without moving the
+    /// cursor. At the ghost position, this is the bottom element.
+    #[must_use]
+    pub fn peek_above(&self) -> Option<&'a T> {
+        let above = match self.current {
+            // SAFETY: the node is owned by the borrowed stack
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.stack.tail,
+        };
+        // SAFETY: the node is owned by the borrowed stack which outlives `'a`
+        above.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// borrows the stack the cursor points into.
+    #[must_use]
+    pub fn as_stack(&self) -> &'a GenericStack<T, A> {
+        self.stack
+    }
+}
+
+/// Cloning a [`Cursor`] does not clone the elements: both cursors point to
+/// the same position of the same stack.
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        Cursor {
+            current: self.current,
+            depth: self.depth,
+            stack: self.stack,
+        }
+    }
+}
+
+// SAFETY: a cursor holds a raw link to the current node, but behaves like
+// a shared borrow of the stack.
+unsafe impl<T: Sync, A: Allocator + Sync> Send for Cursor<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for Cursor<'_, T, A> {}
+
+/// Cursor returned by [`GenericStack::cursor_mut`]: in addition to the
+/// operations of a [`Cursor`], it can modify the stack at its position.
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    /// the node the cursor points to, `None` at the ghost position
+    current: Link<T>,
+    /// the number of elements above the current element. The depth is
+    /// equal to the length of the stack at the ghost position.
+    depth: usize,
+    stack: &'a mut GenericStack<T, A>,
+}
+
+impl<T, A: Allocator> CursorMut<'_, T, A> {
+    /// borrows the element the cursor points to as a mutable value. This
+    /// will return `None` at the ghost position.
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the node is owned by the mutably borrowed stack
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).element })
+    }
+
+    /// Returns the depth of the current element, i.e., the number of
+    /// elements above it: the top element has depth 0. This will return
+    /// `None` at the ghost position.
+    #[must_use]
+    pub fn depth(&self) -> Option<usize> {
+        self.current.map(|_| self.depth)
+    }
+
+    /// Moves the cursor to the element below the current element. At the
+    /// bottom element, the cursor moves to the ghost position, and from the
+    /// ghost position, it moves to the top element.
+    pub fn move_down(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_down();
+        (self.current, self.depth) = (cursor.current, cursor.depth);
+    }
+
+    /// Moves the cursor to the element above the current element. At the
+    /// top element, the cursor moves to the ghost position, and from the
+    /// ghost position, it moves to the bottom element.
+    pub fn move_up(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_up();
+        (self.current, self.depth) = (cursor.current, cursor.depth);
+    }
+
+    /// Moves the cursor back to the top element (or to the ghost position
+    /// if the stack is empty).
+    pub fn rewind(&mut self) {
+        self.current = self.stack.head;
+        self.depth = 0;
+    }
+
+    /// Returns a read-only cursor pointing to the current position. The
+    /// [`CursorMut`] cannot be used while the [`Cursor`] is alive.
+    #[must_use]
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            current: self.current,
+            depth: self.depth,
+            stack: self.stack,
+        }
+    }
+
+    /// Inserts `element` directly below the current element. At the ghost
+    /// position, `element` is pushed on the top of the stack. The cursor
+    /// does not move.
+    pub fn insert_after(&mut self, element: T) {
+        let node = self.stack.allocate_node(element);
+        // SAFETY: the node is unlinked and the current node (if any) is
+        // linked into the stack.
+        unsafe { self.stack.link_node_below(node, self.current) };
+        if self.current.is_none() {
+            // the ghost position stays below the bottom element
+            self.depth += 1;
+        }
+        trace!("insert_after: length {}", self.stack.len);
+    }
+
+    /// Inserts `element` directly above the current element. At the ghost
+    /// position, `element` becomes the bottom element of the stack. The
+    /// cursor does not move.
+    pub fn insert_before(&mut self, element: T) {
+        let above = match self.current {
+            // SAFETY: the node is owned by the mutably borrowed stack
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.stack.tail,
+        };
+        let node = self.stack.allocate_node(element);
+        // SAFETY: the node is unlinked and `above` (if any) is linked into
+        // the stack.
+        unsafe { self.stack.link_node_below(node, above) };
+        self.depth += 1;
+        trace!("insert_before: length {}", self.stack.len);
+    }
+
+    /// Removes the current element from the stack and returns it. The
+    /// cursor moves to the element below, which now has the same depth.
+    /// This will return `None` (and not change the stack) at the ghost
+    /// position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        // SAFETY: the node is linked into the stack. We read its next link
+        // before it is unlinked and recycled.
+        let element = unsafe {
+            self.current = (*node.as_ptr()).next;
+            self.stack.unlink_node(node);
+            self.stack.free_node(node)
+        };
+        trace!("remove_current: length {}", self.stack.len);
+        Some(element)
+    }
+
+    /// Splits the stack below the current element: all elements below the
+    /// current element are moved to a new stack, which is returned. At the
+    /// ghost position, all elements are moved to the new stack. The nodes
+    /// are relinked, i.e., this is O(1). The nodes of the new stack are
+    /// owned by a clone of the allocator.
+    pub fn split_after(&mut self) -> GenericStack<T, A>
+    where
+        A: Clone,
+    {
+        let mut split = GenericStack::new_in(self.stack.alloc.clone());
+        if let Some(node) = self.current {
+            // SAFETY: the current node and the node below it (if any) are
+            // linked into the stack, which we borrow mutably.
+            unsafe {
+                if let Some(below) = (*node.as_ptr()).next.take() {
+                    (*below.as_ptr()).prev = None;
+                    split.head = Some(below);
+                    split.tail = self.stack.tail;
+                    split.len = self.stack.len - self.depth - 1;
+                    self.stack.tail = Some(node);
+                    self.stack.len = self.depth + 1;
+                }
+            }
+        } else {
+            split.head = self.stack.head.take();
+            split.tail = self.stack.tail.take();
+            split.len = self.stack.len;
+            self.stack.len = 0;
+            self.depth = 0;
+        }
+        #[cfg(feature = "stats")]
+        {
+            split.record_moved(&mut self.stack.stats, split.len);
+            split.record_depth();
+        }
+        trace!("split_after({}): length {}", split.len, self.stack.len);
+        split
+    }
+
+    /// borrows the stack the cursor points into.
+    #[must_use]
+    pub fn as_stack(&self) -> &GenericStack<T, A> {
+        self.stack
+    }
+}
+
+// SAFETY: a mutable cursor holds a raw link to the current node, but
+// behaves like a mutable borrow of the stack.
+unsafe impl<T: Send, A: Allocator + Send> Send for CursorMut<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for CursorMut<'_, T, A> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cursor() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        let mut cursor = stack.cursor();
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(cursor.depth(), Some(0));
+        assert_eq!(cursor.peek_above(), None);
+        assert_eq!(cursor.peek_below(), Some(&2));
+
+        cursor.move_down();
+        cursor.move_down();
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.depth(), Some(2));
+        assert_eq!(cursor.peek_above(), Some(&2));
+
+        // below the bottom, the cursor reaches the ghost position
+        cursor.move_down();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.depth(), None);
+        assert_eq!(cursor.peek_below(), Some(&3));
+        assert_eq!(cursor.peek_above(), Some(&1));
+
+        // and wraps around to the top
+        cursor.move_down();
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(cursor.depth(), Some(0));
+    }
+
+    #[test]
+    fn move_up() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        let mut cursor = stack.cursor();
+        cursor.move_up();
+        assert_eq!(cursor.current(), None);
+        cursor.move_up();
+        assert_eq!(cursor.current(), Some(&1));
+        assert_eq!(cursor.depth(), Some(2));
+        cursor.move_up();
+        assert_eq!(cursor.current(), Some(&2));
+        assert_eq!(cursor.depth(), Some(1));
+
+        let clone = cursor.clone();
+        cursor.rewind();
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(clone.current(), Some(&2));
+        assert_eq!(clone.as_stack().len(), 3);
+    }
+
+    #[test]
+    fn empty() {
+        let stack: GenericStack<u8> = GenericStack::new();
+        let mut cursor = stack.cursor();
+        assert_eq!(cursor.current(), None);
+        cursor.move_down();
+        assert_eq!(cursor.current(), None);
+        cursor.move_up();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.depth(), None);
+        assert_eq!(cursor.peek_below(), None);
+    }
+
+    #[test]
+    fn insert() {
+        let mut stack = GenericStack::from(vec![1, 3]);
+        let mut cursor = stack.cursor_mut();
+        cursor.insert_before(4);
+        assert_eq!(cursor.depth(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        cursor.insert_after(2);
+        cursor.move_down();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.depth(), Some(2));
+
+        // at the ghost position, we insert at the bottom and on the top
+        cursor.move_down();
+        cursor.move_down();
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(0);
+        cursor.insert_after(5);
+        cursor.move_up();
+        assert_eq!(cursor.current(), Some(&mut 0));
+        assert_eq!(cursor.depth(), Some(5));
+        assert_eq!(cursor.as_stack().len(), 6);
+
+        assert_eq!(
+            stack.iter().rev().collect::<Vec<_>>(),
+            vec![&0, &1, &2, &3, &4, &5]
+        );
+        assert_eq!(Vec::from(stack), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn remove_current() {
+        let mut stack: GenericStack<_> = (1..=4).collect();
+        let mut cursor = stack.cursor_mut();
+        cursor.move_down();
+        if let Some(current) = cursor.current() {
+            *current *= 10;
+        }
+        assert_eq!(cursor.remove_current(), Some(30));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.depth(), Some(1));
+        cursor.move_down();
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_up();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.rewind();
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.iter().rev().collect::<Vec<_>>(), vec![&2]);
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn split_after() {
+        let mut stack: GenericStack<_> = (1..=5).collect();
+        let mut cursor = stack.cursor_mut();
+        cursor.move_down();
+        let mut below = cursor.split_after();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert!(cursor.split_after().is_empty());
+        assert_eq!(below.len(), 3);
+        assert_eq!(below.iter().rev().collect::<Vec<_>>(), vec![&1, &2, &3]);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.iter().rev().collect::<Vec<_>>(), vec![&4, &5]);
+
+        // at the ghost position, all elements are moved
+        let mut cursor = below.cursor_mut();
+        cursor.move_up();
+        cursor.move_up();
+        assert_eq!(cursor.split_after(), GenericStack::new());
+        let mut cursor = below.cursor_mut();
+        cursor.move_up();
+        let all = cursor.split_after();
+        assert!(below.is_empty());
+        assert_eq!(Vec::from(all), vec![1, 2, 3]);
+        below.push(6);
+        assert_eq!(below.iter().rev().collect::<Vec<_>>(), vec![&6]);
+    }
+}
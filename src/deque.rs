@@ -0,0 +1,258 @@
+//! A double-ended queue built on the nodes of [`GenericStack<T>`].
+
+use crate::{Allocator, GenericStack, Global, IntoIter, Iter, IterMut};
+use core::fmt::{self, Debug};
+
+/// `Deque<T>` is a double-ended queue with O(1) operations at both ends.
+///
+/// Since the nodes of a [`GenericStack<T>`] are linked in both directions
+/// and the stack knows its bottom node, a `Deque<T>` is just a stack whose
+/// top is the front and whose bottom is the back of the queue. It shares
+/// the node pool, the allocator support, and the iterators with the stack.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::Deque;
+/// let mut deque = Deque::new();
+/// deque.push_back(2);
+/// deque.push_front(1);
+/// deque.push_back(3);
+/// assert_eq!(deque.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+/// assert_eq!(deque.pop_front(), Some(1));
+/// assert_eq!(deque.pop_back(), Some(3));
+/// assert_eq!(deque.len(), 1);
+/// ```
+pub struct Deque<T, A: Allocator = Global> {
+    /// the top of the stack is the front of the deque
+    stack: GenericStack<T, A>,
+}
+
+impl<T> Deque<T> {
+    /// Create a new, empty deque.
+    #[must_use]
+    pub fn new() -> Self {
+        Deque {
+            stack: GenericStack::new(),
+        }
+    }
+}
+
+impl<T, A: Allocator> Deque<T, A> {
+    /// Create a new, empty deque whose nodes are allocated with `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Deque {
+            stack: GenericStack::new_in(alloc),
+        }
+    }
+
+    /// inserts `element` at the front of the deque.
+    pub fn push_front(&mut self, element: T) {
+        self.stack.push(element);
+    }
+
+    /// appends `element` at the back of the deque.
+    pub fn push_back(&mut self, element: T) {
+        self.stack.push_bottom(element);
+    }
+
+    /// Removes and returns the element at the front of the deque if it exists.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// Removes and returns the element at the back of the deque if it exists.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.stack.pop_bottom_node()?;
+        trace!("pop_back: length {}", self.stack.len);
+        // SAFETY: the node was allocated by the stack and is unlinked
+        Some(unsafe { self.stack.free_node(node) })
+    }
+
+    /// borrows the element at the front of the deque.
+    pub fn front(&self) -> Option<&T> {
+        self.stack.peek()
+    }
+
+    /// borrows the element at the front of the deque as a mutable value.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.stack.peek_mut()
+    }
+
+    /// borrows the element at the back of the deque.
+    pub fn back(&self) -> Option<&T> {
+        self.stack.peek_bottom()
+    }
+
+    /// borrows the element at the back of the deque as a mutable value.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.stack.peek_bottom_mut()
+    }
+
+    /// iterator from the front to the back of the deque
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.stack.iter()
+    }
+
+    /// mutable iterator from the front to the back of the deque
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.stack.iter_mut()
+    }
+
+    /// Returns the number of elements stored in the deque.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the deque does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Removes all elements from the deque.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+impl<T, A: Allocator + Default> Default for Deque<T, A> {
+    fn default() -> Self {
+        Deque::new_in(A::default())
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for Deque<T, A> {
+    fn clone(&self) -> Self {
+        Deque {
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+/// The deque is printed from the front to the back.
+impl<T: Debug, A: Allocator> Debug for Deque<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for Deque<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.stack == other.stack
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for Deque<T, A> {}
+
+/// The top of the stack becomes the front of the deque.
+impl<T, A: Allocator> From<GenericStack<T, A>> for Deque<T, A> {
+    fn from(stack: GenericStack<T, A>) -> Self {
+        Deque { stack }
+    }
+}
+
+/// The front of the deque becomes the top of the stack.
+impl<T, A: Allocator> From<Deque<T, A>> for GenericStack<T, A> {
+    fn from(deque: Deque<T, A>) -> Self {
+        deque.stack
+    }
+}
+
+/// Collecting appends the elements at the back, i.e., the first element of
+/// the iterator is the front of the deque.
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Deque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+/// Extending appends the elements at the back of the deque.
+impl<T, A: Allocator> Extend<T> for Deque<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push_back(element);
+        }
+    }
+}
+
+/// The consuming iterator returns the elements from the front to the back.
+impl<T, A: Allocator> IntoIterator for Deque<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stack.into_iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a Deque<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut Deque<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn both_ends() {
+        let mut deque = Deque::new();
+        assert_eq!(deque.pop_back(), None);
+        assert_eq!(deque.back(), None);
+        deque.push_back(1);
+        assert_eq!(deque.front(), Some(&1));
+        assert_eq!(deque.back(), Some(&1));
+        deque.push_back(2);
+        deque.push_front(0);
+        assert_eq!(deque.len(), 3);
+        assert_eq!(format!("{deque:?}"), "[0, 1, 2]");
+
+        *deque.front_mut().unwrap() = 10;
+        *deque.back_mut().unwrap() = 20;
+        assert_eq!(deque.iter().copied().collect::<Vec<_>>(), vec![10, 1, 20]);
+        assert_eq!(deque.pop_back(), Some(20));
+        assert_eq!(deque.pop_back(), Some(1));
+        assert_eq!(deque.pop_back(), Some(10));
+        assert!(deque.is_empty());
+
+        // the deque works as a queue in both directions
+        deque.extend(0..5);
+        deque.push_front(-1);
+        assert_eq!(deque.pop_back(), Some(4));
+        assert_eq!(deque.pop_front(), Some(-1));
+        assert_eq!(deque.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn conversions() {
+        let deque: Deque<_> = ["a", "b", "c"].into_iter().map(String::from).collect();
+        assert_eq!(deque.front().map(String::as_str), Some("a"));
+        let clone = deque.clone();
+        assert_eq!(clone, deque);
+
+        let mut stack = GenericStack::from(deque);
+        assert_eq!(stack.pop().as_deref(), Some("a"));
+        let deque = Deque::from(stack);
+        assert_eq!(deque.back().map(String::as_str), Some("c"));
+        assert_ne!(deque, clone);
+        assert_eq!(Deque::<u8>::default(), Deque::new());
+    }
+}
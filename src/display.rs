@@ -0,0 +1,185 @@
+//! Configurable rendering of a [`GenericStack<T>`]: the [`Display`]
+//! implementation of the stack always prints `head->3->2->1.`, while
+//! [`GenericStack::display_with`] permits to choose the separator and the
+//! direction, and to omit the `head` and `.` decorations.
+
+use crate::{Allocator, GenericStack, Global};
+use core::fmt::{self, Display};
+
+/// The order in which [`DisplayWith`] prints the elements of a stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// from the top to the bottom, i.e., in the order of `pop`
+    #[default]
+    TopToBottom,
+    /// from the bottom to the top, i.e., in the order of `push`
+    BottomToTop,
+}
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Returns an adapter that implements [`Display`] and prints the
+    /// elements in the given `direction`, separated by `separator`. In
+    /// contrast to the [`Display`] implementation of the stack, the
+    /// `head` and `.` decorations are omitted unless they are enabled
+    /// with [`DisplayWith::decorated`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::{Direction, GenericStack};
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.display_with(", ", Direction::BottomToTop).to_string(), "1, 2, 3");
+    /// assert_eq!(
+    ///     stack.display_with("->", Direction::TopToBottom).decorated(true).to_string(),
+    ///     stack.to_string()
+    /// );
+    /// ```
+    pub fn display_with<'a>(
+        &'a self,
+        separator: &'a str,
+        direction: Direction,
+    ) -> DisplayWith<'a, T, A> {
+        DisplayWith {
+            stack: self,
+            separator,
+            direction,
+            decorated: false,
+        }
+    }
+}
+
+/// Adapter returned by [`GenericStack::display_with`].
+pub struct DisplayWith<'a, T, A: Allocator = Global> {
+    stack: &'a GenericStack<T, A>,
+    separator: &'a str,
+    direction: Direction,
+    decorated: bool,
+}
+
+impl<T, A: Allocator> DisplayWith<'_, T, A> {
+    /// Enables or disables the decorations: the word `head` at the top of
+    /// the stack and a final `.`.
+    #[must_use]
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    /// writes the elements separated by the separator
+    fn write_elements<'b>(
+        &self,
+        f: &mut fmt::Formatter,
+        elements: impl Iterator<Item = &'b T>,
+    ) -> fmt::Result
+    where
+        T: Display + 'b,
+    {
+        for (i, element) in elements.enumerate() {
+            if i > 0 {
+                f.write_str(self.separator)?;
+            }
+            write!(f, "{element}")?;
+        }
+        Ok(())
+    }
+}
+
+/// [`DisplayWith`] implements trait [`Display`]: the decorations are
+/// written even if the stack is empty.
+///
+/// # Example:
+///
+/// `head->6->4->3->2.` (decorated, from the top to the bottom)
+impl<T: Display, A: Allocator> Display for DisplayWith<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decorate = self.decorated && !self.stack.is_empty();
+        match self.direction {
+            Direction::TopToBottom => {
+                if self.decorated {
+                    write!(f, "head")?;
+                }
+                if decorate {
+                    f.write_str(self.separator)?;
+                }
+                self.write_elements(f, self.stack.iter())?;
+            }
+            Direction::BottomToTop => {
+                self.write_elements(f, self.stack.iter().rev())?;
+                if decorate {
+                    f.write_str(self.separator)?;
+                }
+                if self.decorated {
+                    write!(f, "head")?;
+                }
+            }
+        }
+        if self.decorated {
+            write!(f, ".")?;
+        }
+        Ok(())
+    }
+}
+
+/// writes `elements` from the top to the bottom in the format of the
+/// [`Display`] implementation of [`GenericStack`], e.g., `head->3->2->1.`.
+/// The other stacks of this crate print their elements with this helper.
+pub(crate) fn fmt_head_list<I>(f: &mut fmt::Formatter, elements: I) -> fmt::Result
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    f.write_str("head")?;
+    for v in elements {
+        write!(f, "->{v}")?;
+    }
+    f.write_str(".")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_with() {
+        let stack = GenericStack::from(vec![1, 2, 3]);
+        assert_eq!(
+            stack.display_with(" ", Direction::TopToBottom).to_string(),
+            "3 2 1"
+        );
+        assert_eq!(
+            stack.display_with("<-", Direction::BottomToTop).to_string(),
+            "1<-2<-3"
+        );
+        assert_eq!(
+            stack
+                .display_with("->", Direction::BottomToTop)
+                .decorated(true)
+                .to_string(),
+            "1->2->3->head."
+        );
+        assert_eq!(
+            stack
+                .display_with("->", Direction::default())
+                .decorated(true)
+                .to_string(),
+            "head->3->2->1."
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let stack: GenericStack<u8> = GenericStack::new();
+        assert_eq!(
+            stack.display_with(",", Direction::TopToBottom).to_string(),
+            ""
+        );
+        assert_eq!(
+            stack
+                .display_with(",", Direction::BottomToTop)
+                .decorated(true)
+                .to_string(),
+            "head."
+        );
+        assert_eq!(stack.to_string(), "head.");
+    }
+}
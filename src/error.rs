@@ -0,0 +1,60 @@
+//! Errors returned by the fallible operations of the stacks.
+
+use core::fmt::{self, Debug, Display};
+
+/// Error returned by [`GenericStack::push_checked`](crate::GenericStack::push_checked)
+/// if the stack has reached its maximum depth. The error owns the element
+/// that could not be pushed, which can be recovered with
+/// [`StackFullError::into_inner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StackFullError<T>(pub T);
+
+impl<T> StackFullError<T> {
+    /// Returns the element that could not be pushed.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// The element is not printed, i.e., `T` does not need to implement
+/// [`Display`].
+impl<T> Display for StackFullError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the stack has reached its maximum depth")
+    }
+}
+
+impl<T: Debug> core::error::Error for StackFullError<T> {}
+
+/// Error returned by [`StackExt::try_pop`](crate::StackExt::try_pop) if
+/// the stack is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StackEmpty;
+
+impl Display for StackEmpty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the stack is empty")
+    }
+}
+
+impl core::error::Error for StackEmpty {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn stack_full_error() {
+        let error = StackFullError("element");
+        assert_eq!(error.to_string(), "the stack has reached its maximum depth");
+        let error: &dyn core::error::Error = &error;
+        assert!(error.source().is_none());
+        assert_eq!(StackFullError(1).into_inner(), 1);
+    }
+
+    #[test]
+    fn stack_empty() {
+        assert_eq!(StackEmpty.to_string(), "the stack is empty");
+    }
+}
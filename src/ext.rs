@@ -0,0 +1,222 @@
+//! Extensions of trait [`Stack`] of crate `stack_trait`.
+//!
+//! Trait [`Stack`] is defined in a separate crate and shared by several
+//! stack implementations. We add further methods with the extension trait
+//! [`StackExt`], which every stack of this crate implements. Since the
+//! methods have default implementations based on [`Stack`], implementing
+//! [`StackExt`] for another stack only takes an empty `impl` block. The
+//! stacks of this crate override [`StackExt::len`], whose default
+//! implementation is O(n).
+//!
+//! Stacks that can reserve memory for their elements in advance also
+//! implement [`CapacityStack`].
+
+use crate::{Stack, StackEmpty};
+use core::fmt::{Debug, Display};
+
+/// Methods for every [`Stack`] that go beyond the methods of the trait.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::{GenericStack, Stack, StackEmpty, StackExt};
+///
+/// /// adds the two top elements of the stack
+/// fn add<S: StackExt<i64>>(stack: &mut S) -> Result<(), StackEmpty> {
+///     let a = stack.try_pop()?;
+///     let b = stack.try_pop()?;
+///     stack.push(a + b);
+///     Ok(())
+/// }
+///
+/// let mut stack: GenericStack<i64> = Stack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(add(&mut stack), Ok(()));
+/// assert_eq!(stack.peek(), Some(&3));
+/// assert_eq!(add(&mut stack), Err(StackEmpty));
+/// ```
+pub trait StackExt<T: Debug + PartialEq + Clone + Display>: Stack<T> {
+    /// Returns the number of elements stored on the stack.
+    ///
+    /// The default implementation pops all elements from a clone of the
+    /// stack, i.e., it takes O(n) time and clones the elements. Stacks
+    /// that know their length should override this method.
+    fn len(&self) -> usize {
+        let mut stack = self.clone();
+        let mut len = 0;
+        while stack.pop().is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    fn is_empty(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Removes and returns the top element of the stack. In contrast to
+    /// [`Stack::pop`], this permits to propagate an empty stack as error
+    /// with `?`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StackEmpty`] if the stack is empty.
+    fn try_pop(&mut self) -> Result<T, StackEmpty> {
+        self.pop().ok_or(StackEmpty)
+    }
+
+    /// Removes and returns the top element of the stack, or returns
+    /// `T::default()` if the stack is empty, e.g., a stack machine may
+    /// treat missing operands as zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::{GenericStack, Stack, StackExt};
+    /// let mut stack: GenericStack<i32> = Stack::new();
+    /// stack.push(5);
+    /// assert_eq!(stack.pop_or_default(), 5);
+    /// assert_eq!(stack.pop_or_default(), 0);
+    /// ```
+    fn pop_or_default(&mut self) -> T
+    where
+        T: Default,
+    {
+        self.pop().unwrap_or_default()
+    }
+}
+
+/// A [`Stack`] that can reserve memory for its elements in advance, i.e.,
+/// code that knows how many elements it is going to push can avoid
+/// allocations while staying generic over the stack implementation.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::{CapacityStack, ChunkedStack, Stack, VecStack};
+///
+/// fn fill<S: CapacityStack<u32>>(n: u32) -> S {
+///     let mut stack = S::with_capacity(n as usize);
+///     let capacity = stack.capacity();
+///     for i in 0..n {
+///         stack.push(i);
+///     }
+///     // the stack did not need to grow
+///     assert_eq!(stack.capacity(), capacity);
+///     stack
+/// }
+///
+/// let stack: VecStack<u32> = fill(100);
+/// assert_eq!(stack.len(), 100);
+/// let stack: ChunkedStack<u32> = fill(100);
+/// assert_eq!(stack.len(), 100);
+/// ```
+pub trait CapacityStack<T: Debug + PartialEq + Clone + Display>: Stack<T> {
+    /// Create a new, empty stack that can store at least `capacity`
+    /// elements without allocating.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Returns the number of elements the stack can store without
+    /// allocating, including the elements stored on the stack.
+    fn capacity(&self) -> usize;
+
+    /// Makes sure that at least `additional` more elements can be pushed
+    /// without allocating.
+    fn reserve(&mut self, additional: usize);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ArrayStack, ChunkedStack, GenericStack, MinStack, VecStack};
+
+    fn reserve_all<S: CapacityStack<u8>>() {
+        let mut stack = S::with_capacity(3);
+        assert!(stack.capacity() >= 3);
+        stack.push(1);
+        stack.reserve(2);
+        let capacity = stack.capacity();
+        assert!(capacity >= 3);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.capacity(), capacity);
+    }
+
+    #[test]
+    fn capacity() {
+        reserve_all::<GenericStack<u8>>();
+        reserve_all::<VecStack<u8>>();
+        reserve_all::<ArrayStack<u8, 3>>();
+        reserve_all::<ChunkedStack<u8, 2>>();
+    }
+
+    fn try_pop_all<S: StackExt<u8>>() {
+        let mut stack = S::new();
+        assert!(StackExt::is_empty(&stack));
+        stack.push(1);
+        assert_eq!(StackExt::len(&stack), 1);
+        assert!(!StackExt::is_empty(&stack));
+        assert_eq!(stack.try_pop(), Ok(1));
+        assert_eq!(stack.try_pop(), Err(StackEmpty));
+        stack.push(2);
+        assert_eq!(stack.pop_or_default(), 2);
+        assert_eq!(stack.pop_or_default(), 0);
+    }
+
+    /// a stack with the default implementations of [`StackExt`]
+    #[derive(Debug, Clone, PartialEq)]
+    struct Plain(Vec<u8>);
+
+    impl Display for Plain {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    impl Stack<u8> for Plain {
+        fn new() -> Self {
+            Plain(Vec::new())
+        }
+
+        fn push(&mut self, element: u8) {
+            self.0.push(element);
+        }
+
+        fn pop(&mut self) -> Option<u8> {
+            self.0.pop()
+        }
+
+        fn peek(&self) -> Option<&u8> {
+            self.0.last()
+        }
+
+        fn peek_mut(&mut self) -> Option<&mut u8> {
+            self.0.last_mut()
+        }
+    }
+
+    impl StackExt<u8> for Plain {}
+
+    #[test]
+    fn default_len() {
+        let mut stack = Plain::new();
+        assert_eq!(StackExt::len(&stack), 0);
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(StackExt::len(&stack), 2);
+        // the stack itself is not changed
+        assert_eq!(stack.peek(), Some(&2));
+    }
+
+    #[test]
+    fn try_pop() {
+        try_pop_all::<GenericStack<u8>>();
+        try_pop_all::<VecStack<u8>>();
+        try_pop_all::<ArrayStack<u8, 2>>();
+        try_pop_all::<ChunkedStack<u8>>();
+        try_pop_all::<MinStack<u8>>();
+        try_pop_all::<Plain>();
+    }
+}
@@ -0,0 +1,256 @@
+//! A C API of the stack.
+//!
+//! The stack is an opaque handle [`LlStack`] whose elements are byte
+//! buffers, i.e., C and C++ code can store any data by copying it into the
+//! stack. All functions are `extern "C"` and not mangled, and the header
+//! can be generated with [cbindgen](https://github.com/mozilla/cbindgen):
+//!
+//! `cbindgen --config cbindgen.toml --output ll_stack.h`
+//!
+//! Build the shared library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib` (or a
+//! static library with `--crate-type staticlib`).
+//!
+//! ```c
+//! LlStack *stack = ll_stack_new();
+//! int value = 42;
+//! ll_stack_push(stack, (const uint8_t *)&value, sizeof value);
+//!
+//! int top;
+//! size_t len;
+//! if (ll_stack_pop(stack, (uint8_t *)&top, sizeof top, &len) == LL_STACK_STATUS_OK) {
+//!     printf("%d\n", top);
+//! }
+//! ll_stack_free(stack);
+//! ```
+//!
+//! A handle must not be used by several threads at the same time.
+
+use crate::GenericStack;
+use alloc::boxed::Box;
+use core::ptr;
+use core::slice;
+
+/// The opaque handle of a stack of byte buffers. It is created by
+/// [`ll_stack_new`] and must be freed with [`ll_stack_free`].
+pub struct LlStack {
+    stack: GenericStack<Box<[u8]>>,
+}
+
+/// The result of [`ll_stack_pop`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlStackStatus {
+    /// the top element was copied into the buffer and removed
+    Ok = 0,
+    /// the stack is empty
+    Empty = 1,
+    /// the buffer is too small, i.e., the stack is not changed; the length
+    /// of the top element is stored in `len`
+    BufferTooSmall = 2,
+    /// the stack or another required pointer is null
+    NullPointer = 3,
+}
+
+/// Creates a new, empty stack. The returned handle is never null.
+#[no_mangle]
+pub extern "C" fn ll_stack_new() -> *mut LlStack {
+    Box::into_raw(Box::new(LlStack {
+        stack: GenericStack::new(),
+    }))
+}
+
+/// Frees the stack and all its elements. `stack` may be null.
+///
+/// # Safety
+///
+/// `stack` must be null or a handle returned by [`ll_stack_new`] which was
+/// not freed yet. The handle must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_free(stack: *mut LlStack) {
+    if !stack.is_null() {
+        // SAFETY: the handle was created by `ll_stack_new` and is freed once
+        drop(unsafe { Box::from_raw(stack) });
+    }
+}
+
+/// Copies `len` bytes from `data` into a new element on the top of the
+/// stack. `data` may be null if `len` is 0. Returns `false` if `stack` is
+/// null or `data` is null while `len` is not 0.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle, and `data` must point to `len`
+/// readable bytes unless it is null.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_push(stack: *mut LlStack, data: *const u8, len: usize) -> bool {
+    // SAFETY: the caller guarantees that the handle is valid
+    let Some(stack) = (unsafe { stack.as_mut() }) else {
+        return false;
+    };
+    let element = if len == 0 {
+        Box::default()
+    } else if data.is_null() {
+        return false;
+    } else {
+        // SAFETY: the caller guarantees that `data` points to `len` bytes
+        Box::from(unsafe { slice::from_raw_parts(data, len) })
+    };
+    stack.stack.push(element);
+    true
+}
+
+/// Copies the top element into `buffer` and removes it from the stack if
+/// the element fits into the `capacity` bytes of the buffer. The length of
+/// the top element is stored in `len` unless the stack is empty.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle, `buffer` must be null or point
+/// to `capacity` writable bytes, and `len` must be null or point to a
+/// writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_pop(
+    stack: *mut LlStack,
+    buffer: *mut u8,
+    capacity: usize,
+    len: *mut usize,
+) -> LlStackStatus {
+    // SAFETY: the caller guarantees that the handle is valid
+    let Some(stack) = (unsafe { stack.as_mut() }) else {
+        return LlStackStatus::NullPointer;
+    };
+    let Some(top) = stack.stack.peek() else {
+        return LlStackStatus::Empty;
+    };
+    if !len.is_null() {
+        // SAFETY: the caller guarantees that `len` is writable
+        unsafe { len.write(top.len()) };
+    }
+    if top.len() > capacity {
+        return LlStackStatus::BufferTooSmall;
+    }
+    if buffer.is_null() && !top.is_empty() {
+        return LlStackStatus::NullPointer;
+    }
+    if !top.is_empty() {
+        // SAFETY: the buffer has room for `capacity >= top.len()` bytes and
+        // cannot overlap with the element owned by the stack
+        unsafe { ptr::copy_nonoverlapping(top.as_ptr(), buffer, top.len()) };
+    }
+    stack.stack.pop();
+    LlStackStatus::Ok
+}
+
+/// Returns a pointer to the bytes of the top element and stores its length
+/// in `len`, or returns null if the stack is empty or null. The pointer is
+/// valid until the stack is modified or freed.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle, and `len` must be null or point
+/// to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_peek(stack: *const LlStack, len: *mut usize) -> *const u8 {
+    // SAFETY: the caller guarantees that the handle is valid
+    let Some(top) = (unsafe { stack.as_ref() }).and_then(|stack| stack.stack.peek()) else {
+        return ptr::null();
+    };
+    if !len.is_null() {
+        // SAFETY: the caller guarantees that `len` is writable
+        unsafe { len.write(top.len()) };
+    }
+    top.as_ptr()
+}
+
+/// Returns the number of elements stored on the stack, or 0 if `stack` is
+/// null.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_len(stack: *const LlStack) -> usize {
+    // SAFETY: the caller guarantees that the handle is valid
+    unsafe { stack.as_ref() }.map_or(0, |stack| stack.stack.len())
+}
+
+/// Returns `true` if the stack does not contain any elements or is null.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_is_empty(stack: *const LlStack) -> bool {
+    // SAFETY: the caller guarantees that the handle is valid
+    unsafe { ll_stack_len(stack) == 0 }
+}
+
+/// Removes all elements from the stack. Does nothing if `stack` is null.
+///
+/// # Safety
+///
+/// `stack` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn ll_stack_clear(stack: *mut LlStack) {
+    // SAFETY: the caller guarantees that the handle is valid
+    if let Some(stack) = unsafe { stack.as_mut() } {
+        stack.stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_pop() {
+        let stack = ll_stack_new();
+        unsafe {
+            assert!(ll_stack_is_empty(stack));
+            let one = 1u32.to_ne_bytes();
+            assert!(ll_stack_push(stack, one.as_ptr(), one.len()));
+            assert!(ll_stack_push(stack, b"hello".as_ptr(), 5));
+            assert!(ll_stack_push(stack, ptr::null(), 0));
+            assert!(!ll_stack_push(stack, ptr::null(), 1));
+            assert_eq!(ll_stack_len(stack), 3);
+
+            let mut buffer = [0u8; 8];
+            let mut len = usize::MAX;
+            let status = ll_stack_pop(stack, ptr::null_mut(), 0, &mut len);
+            assert_eq!((status, len), (LlStackStatus::Ok, 0));
+
+            let top = ll_stack_peek(stack, &mut len);
+            assert_eq!(slice::from_raw_parts(top, len), b"hello");
+            let status = ll_stack_pop(stack, buffer.as_mut_ptr(), 2, &mut len);
+            assert_eq!((status, len), (LlStackStatus::BufferTooSmall, 5));
+            let status = ll_stack_pop(stack, buffer.as_mut_ptr(), buffer.len(), &mut len);
+            assert_eq!((status, &buffer[..len]), (LlStackStatus::Ok, &b"hello"[..]));
+
+            let status = ll_stack_pop(stack, buffer.as_mut_ptr(), buffer.len(), &mut len);
+            assert_eq!((status, &buffer[..len]), (LlStackStatus::Ok, &one[..]));
+            let status = ll_stack_pop(stack, buffer.as_mut_ptr(), buffer.len(), &mut len);
+            assert_eq!(status, LlStackStatus::Empty);
+            assert!(ll_stack_peek(stack, &mut len).is_null());
+
+            assert!(ll_stack_push(stack, b"x".as_ptr(), 1));
+            ll_stack_clear(stack);
+            assert_eq!(ll_stack_len(stack), 0);
+            ll_stack_free(stack);
+        }
+    }
+
+    #[test]
+    fn null_handles() {
+        unsafe {
+            assert!(!ll_stack_push(ptr::null_mut(), b"x".as_ptr(), 1));
+            let status = ll_stack_pop(ptr::null_mut(), ptr::null_mut(), 0, ptr::null_mut());
+            assert_eq!(status, LlStackStatus::NullPointer);
+            assert!(ll_stack_peek(ptr::null(), ptr::null_mut()).is_null());
+            assert_eq!(ll_stack_len(ptr::null()), 0);
+            assert!(ll_stack_is_empty(ptr::null()));
+            ll_stack_clear(ptr::null_mut());
+            ll_stack_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Reports the heap memory used by a stack.
+
+use crate::{Allocator, GenericStack, Node};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+/// The number of nodes of a [`GenericStack`] and the bytes they occupy on
+/// the heap, as returned by [`GenericStack::memory_footprint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoryFootprint {
+    /// the nodes which store an element, i.e., the length of the stack
+    pub nodes: usize,
+    /// the nodes in the node pool, i.e., allocated but unused nodes
+    pub pooled_nodes: usize,
+    /// the bytes of all nodes: each node stores an element and two links
+    pub bytes: usize,
+}
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Returns the number of nodes and the bytes they occupy on the heap,
+    /// including the node pool. The bytes only contain `size_of::<T>()`
+    /// per element; memory that the elements own themselves, e.g., the
+    /// buffer of a `String`, is reported by [`HeapSize::heap_size`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<u64> = (0..10).collect();
+    /// stack.reserve(5);
+    /// let footprint = stack.memory_footprint();
+    /// assert_eq!(footprint.nodes, 10);
+    /// assert_eq!(footprint.pooled_nodes, 5);
+    /// // a node stores the element and two pointers
+    /// let node = size_of::<u64>() + 2 * size_of::<usize>();
+    /// assert_eq!(footprint.bytes, 15 * node);
+    /// ```
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        MemoryFootprint {
+            nodes: self.len,
+            pooled_nodes: self.pool_len,
+            bytes: (self.len + self.pool_len) * mem::size_of::<Node<T>>(),
+        }
+    }
+}
+
+/// `HeapSize` reports the bytes a value owns on the heap, excluding the
+/// value itself, e.g., the capacity of a `String`. The implementation for
+/// [`GenericStack`] adds the heap size of the elements to its
+/// [`MemoryFootprint`], i.e., the heap size of nested data is counted
+/// recursively.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::{GenericStack, HeapSize};
+/// let mut stack = GenericStack::new();
+/// stack.push(String::with_capacity(100));
+/// let nodes = stack.memory_footprint().bytes;
+/// assert_eq!(stack.heap_size(), nodes + 100);
+/// ```
+pub trait HeapSize {
+    /// Returns the number of bytes owned by `self` on the heap.
+    fn heap_size(&self) -> usize;
+}
+
+/// The values of these types do not own heap memory.
+macro_rules! impl_heap_size_zero {
+    ($($t:ty),*) => {
+        $(
+            impl HeapSize for $t {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_heap_size_zero!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    &str
+);
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<T>() + self.iter().map(T::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        mem::size_of::<T>() + T::heap_size(self)
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, T::heap_size)
+    }
+}
+
+impl<T: HeapSize, const N: usize> HeapSize for [T; N] {
+    fn heap_size(&self) -> usize {
+        self.iter().map(T::heap_size).sum()
+    }
+}
+
+impl<T: HeapSize, U: HeapSize> HeapSize for (T, U) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl<T: HeapSize, A: Allocator> HeapSize for GenericStack<T, A> {
+    fn heap_size(&self) -> usize {
+        self.memory_footprint().bytes + self.iter().map(T::heap_size).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn memory_footprint() {
+        let mut stack: GenericStack<u32> = GenericStack::new();
+        assert_eq!(stack.memory_footprint(), MemoryFootprint::default());
+        stack.extend(0..4);
+        stack.pop();
+        let footprint = stack.memory_footprint();
+        assert_eq!((footprint.nodes, footprint.pooled_nodes), (3, 1));
+        assert_eq!(footprint.bytes, 4 * mem::size_of::<Node<u32>>());
+        assert_eq!(stack.heap_size(), footprint.bytes);
+    }
+
+    #[test]
+    fn nested_heap_size() {
+        let mut inner: GenericStack<Vec<u8>> = GenericStack::new();
+        inner.push(Vec::with_capacity(10));
+        inner.push(Vec::new());
+        let inner_size = 2 * mem::size_of::<Node<Vec<u8>>>() + 10;
+        assert_eq!(inner.heap_size(), inner_size);
+
+        let mut outer = GenericStack::new();
+        outer.push(inner);
+        outer.push(GenericStack::new());
+        let nodes = 2 * mem::size_of::<Node<GenericStack<Vec<u8>>>>();
+        assert_eq!(outer.heap_size(), nodes + inner_size);
+
+        assert_eq!(Some(Box::new(1u64)).heap_size(), 8);
+        assert_eq!(("abc", String::from("abc")).heap_size(), 3);
+    }
+}
@@ -0,0 +1,344 @@
+//! An intrusive stack which never allocates.
+//!
+//! [`Stack<'a, T>`] does not own nodes: the link to the element below is
+//! a [`Link<T>`] field inside the element itself, which the stack finds
+//! with the help of the [`Adapter`] trait. The stack holds exclusive
+//! borrows of its elements, i.e., the elements live wherever the user
+//! keeps them, e.g., in an array, and `push` and `pop` only rewrite two
+//! pointers. This is the classic layout of free lists in kernels and
+//! allocators.
+//!
+//! # Example
+//!
+//! ```
+//! use ll_stack::intrusive::{Adapter, Link, Stack};
+//!
+//! struct Block {
+//!     data: [u8; 64],
+//!     link: Link<Block>,
+//! }
+//!
+//! // SAFETY: both methods return the same field
+//! unsafe impl Adapter for Block {
+//!     fn link(&self) -> &Link<Self> {
+//!         &self.link
+//!     }
+//!
+//!     fn link_mut(&mut self) -> &mut Link<Self> {
+//!         &mut self.link
+//!     }
+//! }
+//!
+//! let mut blocks: Vec<Block> = (0..4)
+//!     .map(|_| Block { data: [0; 64], link: Link::new() })
+//!     .collect();
+//! let mut free_list = Stack::new();
+//! free_list.extend(&mut blocks);
+//!
+//! let block = free_list.pop().unwrap();
+//! block.data[0] = 42;
+//! free_list.push(block);
+//! assert_eq!(free_list.len(), 4);
+//! assert_eq!(free_list.peek().unwrap().data[0], 42);
+//! ```
+
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// The link to the element below, embedded into an element of type `T`.
+/// Only a [`Stack`] reads and writes the link.
+pub struct Link<T> {
+    next: Option<NonNull<T>>,
+}
+
+impl<T> Link<T> {
+    /// Create a new link, which is not part of a stack.
+    #[must_use]
+    pub const fn new() -> Self {
+        Link { next: None }
+    }
+}
+
+impl<T> Default for Link<T> {
+    fn default() -> Self {
+        Link::new()
+    }
+}
+
+/// A link does not print the address it points to.
+impl<T> Debug for Link<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Link")
+    }
+}
+
+// SAFETY: a link is only dereferenced by the stack which holds the
+// exclusive borrows of the element containing the link and the element it
+// points to.
+unsafe impl<T: Send> Send for Link<T> {}
+unsafe impl<T: Sync> Sync for Link<T> {}
+
+/// `Adapter` tells a [`Stack`] where the [`Link`] of an element is.
+///
+/// # Safety
+///
+/// `link` and `link_mut` must always return the same field of `self`, and
+/// must not modify or replace any link, because the stack follows the
+/// links without further checks.
+pub unsafe trait Adapter: Sized {
+    /// borrows the link of `self`
+    fn link(&self) -> &Link<Self>;
+
+    /// mutably borrows the link of `self`
+    fn link_mut(&mut self) -> &mut Link<Self>;
+}
+
+/// `Stack<'a, T>` is an intrusive stack of elements borrowed mutably for
+/// the lifetime `'a`. The elements do not move; only their links are
+/// modified. Pushing and popping never allocate.
+///
+/// The stack only hands out shared borrows of the elements it holds, i.e.,
+/// the link of an element cannot be replaced until the element is popped
+/// again. Neither through the element itself,
+///
+/// ```compile_fail,E0506
+/// # use ll_stack::intrusive::{Adapter, Link, Stack};
+/// # struct Item { link: Link<Item> }
+/// # unsafe impl Adapter for Item {
+/// #     fn link(&self) -> &Link<Self> { &self.link }
+/// #     fn link_mut(&mut self) -> &mut Link<Self> { &mut self.link }
+/// # }
+/// let mut item = Item { link: Link::new() };
+/// let mut stack = Stack::new();
+/// stack.push(&mut item);
+/// item.link = Link::new();
+/// stack.pop();
+/// ```
+///
+/// nor through the stack:
+///
+/// ```compile_fail,E0596
+/// # use ll_stack::intrusive::{Adapter, Link, Stack};
+/// # struct Item { link: Link<Item> }
+/// # unsafe impl Adapter for Item {
+/// #     fn link(&self) -> &Link<Self> { &self.link }
+/// #     fn link_mut(&mut self) -> &mut Link<Self> { &mut self.link }
+/// # }
+/// let mut item = Item { link: Link::new() };
+/// let mut stack = Stack::new();
+/// stack.push(&mut item);
+/// let top = stack.peek().unwrap();
+/// std::mem::take(&mut top.link);
+/// ```
+pub struct Stack<'a, T: Adapter> {
+    head: Option<NonNull<T>>,
+    len: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: Adapter> Stack<'a, T> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub const fn new() -> Self {
+        Stack {
+            head: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// push `element` on the top of the stack by linking it to the
+    /// current top. The stack keeps the element borrowed until it is
+    /// popped.
+    pub fn push(&mut self, element: &'a mut T) {
+        element.link_mut().next = self.head;
+        self.head = Some(NonNull::from(element));
+        self.len += 1;
+    }
+
+    /// Removes the top element from the stack and returns its borrow, or
+    /// `None` if the stack is empty.
+    pub fn pop(&mut self) -> Option<&'a mut T> {
+        self.head.map(|head| {
+            // SAFETY: `head` was created from a `&'a mut T` in `push`, and
+            // the stack gives up that borrow only here.
+            let element = unsafe { &mut *head.as_ptr() };
+            self.head = element.link_mut().next.take();
+            self.len -= 1;
+            element
+        })
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: the stack holds the exclusive borrow of the element.
+        self.head.map(|head| unsafe { &*head.as_ptr() })
+    }
+
+    /// iterator from the top to the bottom of the stack
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+}
+
+impl<T: Adapter> Default for Stack<'_, T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+impl<T: Adapter + Debug> Debug for Stack<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Pushes the elements in the order of the iterator, i.e., the last
+/// element ends up on the top.
+impl<'a, T: Adapter> Extend<&'a mut T> for Stack<'a, T> {
+    fn extend<I: IntoIterator<Item = &'a mut T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+// SAFETY: the stack behaves like a `Vec<&'a mut T>`.
+unsafe impl<T: Adapter + Send> Send for Stack<'_, T> {}
+unsafe impl<T: Adapter + Sync> Sync for Stack<'_, T> {}
+
+impl<'b, T: Adapter> IntoIterator for &'b Stack<'_, T> {
+    type Item = &'b T;
+    type IntoIter = Iter<'b, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of an intrusive [`Stack`] from the top to
+/// the bottom.
+pub struct Iter<'b, T> {
+    next: Option<NonNull<T>>,
+    len: usize,
+    marker: PhantomData<&'b T>,
+}
+
+impl<'b, T: Adapter> Iterator for Iter<'b, T> {
+    type Item = &'b T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            // SAFETY: the iterator borrows the stack, which holds the
+            // exclusive borrow of the element.
+            let element = unsafe { &*node.as_ptr() };
+            self.next = element.link().next;
+            self.len -= 1;
+            element
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: Adapter> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T: Adapter> FusedIterator for Iter<'_, T> {}
+
+// SAFETY: the iterator behaves like a `&'b T`.
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use static_assertions::assert_impl_all;
+
+    #[derive(Debug)]
+    struct Item {
+        value: i32,
+        link: Link<Item>,
+    }
+
+    impl Item {
+        fn new(value: i32) -> Self {
+            Item {
+                value,
+                link: Link::new(),
+            }
+        }
+    }
+
+    unsafe impl Adapter for Item {
+        fn link(&self) -> &Link<Self> {
+            &self.link
+        }
+
+        fn link_mut(&mut self) -> &mut Link<Self> {
+            &mut self.link
+        }
+    }
+
+    assert_impl_all!(Stack<'static, Item>: Send, Sync);
+    assert_impl_all!(Iter<'static, Item>: Send, Sync);
+
+    #[test]
+    fn basics() {
+        let mut items: Vec<Item> = (0..3).map(Item::new).collect();
+        let mut stack = Stack::new();
+        assert!(stack.pop().is_none());
+        assert!(stack.peek().is_none());
+
+        stack.extend(&mut items);
+        assert_eq!(stack.len(), 3);
+        let values: Vec<i32> = stack.iter().map(|item| item.value).collect();
+        assert_eq!(values, vec![2, 1, 0]);
+
+        let top = stack.pop().unwrap();
+        top.value = 20;
+        assert_eq!(stack.peek().unwrap().value, 1);
+        stack.push(top);
+        assert_eq!(stack.iter().len(), 3);
+
+        while let Some(item) = stack.pop() {
+            item.value += 100;
+        }
+        assert!(stack.is_empty());
+        let values: Vec<i32> = items.iter().map(|item| item.value).collect();
+        assert_eq!(values, vec![100, 101, 120]);
+    }
+
+    #[test]
+    fn elements_do_not_move() {
+        let mut items: Vec<Item> = (0..4).map(Item::new).collect();
+        let addresses: Vec<*const Item> = items.iter().map(|item| item as *const Item).collect();
+        let mut stack = Stack::new();
+        stack.extend(&mut items);
+        let popped: Vec<*const Item> = core::iter::from_fn(|| stack.pop())
+            .map(|item| item as *const Item)
+            .collect();
+        assert_eq!(popped, addresses.into_iter().rev().collect::<Vec<_>>());
+    }
+}
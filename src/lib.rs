@@ -1,22 +1,207 @@
+// Without feature `std`, we only depend on `core` and `alloc`. The tests
+// always link `std`, e.g., for threads.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::alloc::handle_alloc_error;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::cmp::Ordering;
 // A trait which implements the print marker: `{:?}`.
 use core::fmt::Debug;
+use core::fmt::{self, Display};
+use core::hash::{Hash, Hasher};
+use core::iter::{Enumerate, FusedIterator, Zip};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Index, IndexMut};
+use core::ptr::{self, NonNull};
+#[cfg(feature = "std")]
 pub use stack_trait::Stack;
-use std::fmt;
-use std::fmt::Display;
+
+pub use allocator_api2::alloc::{AllocError, Allocator, Global};
+
+/// With feature `log`, the mutators of [`GenericStack`] emit a `trace!`
+/// message of crate `log` with the name of the operation and the new
+/// length of the stack. The inherent mutators do not require `T: Debug`,
+/// i.e., only `push` and `pop` of the [`Stack`] implementation also log the
+/// element. Without feature `log`, this expands to nothing.
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)+)
+    };
+}
+
+mod array;
+#[cfg(feature = "async")]
+mod async_stack;
+mod bounded;
+mod checkpoint;
+mod chunked;
+pub mod concurrent;
+mod cursor;
+mod deque;
+mod display;
+mod error;
+#[cfg(feature = "std")]
+mod ext;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod heap_size;
+pub mod intrusive;
+mod min;
+mod persistent;
+mod pinned;
+#[cfg(feature = "python")]
+mod python;
+mod queue;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod shared;
+mod small;
+mod sort;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "stream")]
+mod stream;
+pub mod tracked;
+mod undo;
+mod vec_stack;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use array::ArrayStack;
+#[cfg(feature = "async")]
+pub use async_stack::{AsyncStack, Pop};
+pub use bounded::BoundedStack;
+pub use checkpoint::CheckpointId;
+pub use chunked::ChunkedStack;
+pub use cursor::{Cursor, CursorMut};
+pub use deque::Deque;
+pub use display::{Direction, DisplayWith};
+pub use error::{StackEmpty, StackFullError};
+#[cfg(feature = "std")]
+pub use ext::{CapacityStack, StackExt};
+pub use heap_size::{HeapSize, MemoryFootprint};
+pub use min::MinStack;
+pub use persistent::{
+    ArcIter, ArcPointer, ArcStack, PersistentIter, PersistentStack, RcPointer, SharedPointer,
+};
+pub use pinned::PinnedStack;
+#[cfg(feature = "python")]
+pub use python::{PyStack, PyStackIter};
+pub use queue::TwoStackQueue;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impl::ArchivedStack;
+pub use shared::SharedStack;
+pub use small::SmallStack;
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+#[cfg(feature = "proptest")]
+pub use strategy::any_stack;
+#[cfg(feature = "stream")]
+pub use stream::IntoStream;
+#[cfg(all(feature = "stream", feature = "async"))]
+pub use stream::PopStream;
+pub use tracked::{Aggregate, TrackedStack};
+pub use undo::UndoStack;
+pub use vec_stack::VecStack;
+#[cfg(feature = "wasm")]
+pub use wasm::JsStack;
 
 /// `GenericStack<T>` is a linked-list based implementation of a stack:
 /// It implements the trait [`Stack`], i.e., methods [`Stack::push`], [`Stack::pop`], [`Stack::peek`], and [`Stack::peek_mut`].
 ///
 /// Traits:
 ///
-/// [`GenericStack<T>`]implements the following traits:
+/// [`GenericStack<T>`]implements the following traits whenever `T` implements them:
 ///
 ///  - [`Debug`] since an implementation of [`Stack`] is required to implement trait [`Debug`]
 ///  - [`PartialEq`] since we want to support `assert_eq` in our code examples
+///  - [`Eq`] to support collections requiring total equality like `HashSet`
 ///  - [`Clone`] since an implementation of [`Stack`] is required to implement trait [`Clone]`
 ///  - [`Display`] since an implementation of [`Stack`] is required to implement trait [`Display`]
 ///
-/// It also implements iterators with the help of some helper types.
+/// The core operations (`new`, `push`, `pop`, `peek`, `peek_mut`, and the
+/// iterators) are available for any `T`.
+///
+/// It also implements iterators with the help of some helper types, and a
+/// [`Cursor`] which can move up and down the stack. A [`CursorMut`] can
+/// also insert and remove elements in the middle of the stack in O(1).
+///
+/// With feature `serde`, [`GenericStack<T>`] also implements `Serialize`
+/// and `Deserialize`: a stack is serialized as a sequence from the bottom
+/// to the top, i.e., a round trip preserves the LIFO order. With feature
+/// `rkyv`, a stack is archived as an `ArchivedStack<T>` in the same order.
+///
+/// # Allocators
+///
+/// The nodes of the stack are allocated with an [`Allocator`], which
+/// defaults to the [`Global`] allocator. On stable Rust, we use the
+/// allocator API of crate [`allocator_api2`]; feature `nightly` switches to
+/// the (unstable) allocator API of the standard library. Use
+/// [`GenericStack::new_in`] to allocate the nodes from an arena or a
+/// custom pool:
+///
+/// ```
+/// use core::alloc::Layout;
+/// use core::cell::Cell;
+/// use core::ptr::NonNull;
+/// use ll_stack::{AllocError, Allocator, GenericStack, Global};
+///
+/// /// counts the nodes that are currently allocated
+/// #[derive(Default)]
+/// struct Counting(Cell<usize>);
+///
+/// unsafe impl Allocator for Counting {
+///     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+///         self.0.set(self.0.get() + 1);
+///         Global.allocate(layout)
+///     }
+///
+///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+///         self.0.set(self.0.get() - 1);
+///         Global.deallocate(ptr, layout)
+///     }
+/// }
+///
+/// let counting = Counting::default();
+/// let mut stack = GenericStack::new_in(&counting);
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(counting.0.get(), 2);
+/// // the node of the popped element is kept in the node pool
+/// stack.pop();
+/// assert_eq!(counting.0.get(), 2);
+/// stack.shrink_pool();
+/// assert_eq!(counting.0.get(), 1);
+/// ```
+///
+/// # Node Pool
+///
+/// The nodes of popped elements are not deallocated but kept in a node
+/// pool (a free list) and reused by subsequent pushes, i.e., a stack that
+/// is pushed and popped repeatedly only allocates until it reaches its
+/// maximal length. Like the capacity of a `Vec`, the pool only shrinks
+/// on request: see [`GenericStack::reserve`], [`GenericStack::capacity`],
+/// and [`GenericStack::shrink_pool`].
+///
+/// # Thread Safety
+///
+/// [`GenericStack<T>`] is [`Send`] if `T` is [`Send`] and [`Sync`] if `T`
+/// is [`Sync`], like a `Vec<T>` (and the allocator must be [`Send`] or
+/// [`Sync`], respectively). The same holds for the consuming
+/// iterators. [`Iter`] is [`Send`] and [`Sync`] if `T` is [`Sync`]. These
+/// guarantees are checked at compile time in our tests.
 ///
 /// # Dependency
 ///
@@ -24,262 +209,2585 @@ use std::fmt::Display;
 ///
 /// `ll_stack = {version = "*", git = "https://github.com/IntroductionToRust/ll_stack" }`
 ///
+/// The crate supports `no_std` targets with an allocator: disable the
+/// default feature `std` to only depend on `core` and `alloc`. Without
+/// feature `std`, trait [`Stack`] (which depends on `std`) is not
+/// implemented, but all inherent methods of [`GenericStack<T>`] are available.
+///
 /// # Example
 ///
 /// We added an example on how to use this stack at <https://github.com/IntroductionToRust/stack_main>
-
-#[derive(Debug, PartialEq, Clone)]
-pub struct GenericStack<T: Debug + PartialEq + Display + Clone> {
+pub struct GenericStack<T, A: Allocator = Global> {
     head: Link<T>,
+    /// the bottom node of the stack, i.e., the node that was pushed first.
+    /// The tail is `None` if the stack is empty.
+    tail: Link<T>,
+    len: usize,
+    /// unused nodes linked by their `next` link. The elements of these
+    /// nodes are not initialized.
+    pool: Link<T>,
+    /// the number of nodes in the pool
+    pool_len: usize,
+    /// the maximum number of elements accepted by `push_checked`
+    max_depth: usize,
+    /// allocates and deallocates the nodes of this stack
+    alloc: A,
+    /// counts the pushes and pops and the maximum length
+    #[cfg(feature = "stats")]
+    stats: Stats,
+    /// the stack owns its nodes (and hence, the elements)
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+/// [`GenericStack<T>`] implements trait [`Debug`]: we print the linked
+/// list starting at the head. The back links are not printed.
+///
+/// The alternate format (`{:#?}`) prints one element per line together
+/// with its depth, and marks the top element:
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let stack = GenericStack::from(vec![1, 2]);
+/// assert_eq!(
+///     format!("{stack:#?}"),
+///     "GenericStack (len: 2) [\n    0: 2 <- top\n    1: 1\n]"
+/// );
+/// ```
+#[allow(clippy::missing_fields_in_debug)]
+impl<T: Debug, A: Allocator> Debug for GenericStack<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            writeln!(f, "GenericStack (len: {}) [", self.len)?;
+            for (depth, element) in self.iter().enumerate() {
+                write!(f, "    {depth}: {element:?}")?;
+                if depth == 0 {
+                    write!(f, " <- top")?;
+                }
+                writeln!(f)?;
+            }
+            return write!(f, "]");
+        }
+        f.debug_struct("GenericStack")
+            // SAFETY: the head is owned by this stack and lives as long as `&self`.
+            .field("head", &self.head.map(|node| unsafe { node.as_ref() }))
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`PartialEq`]: two stacks are
+/// equal if they have the same length and the same elements. We cannot
+/// derive [`PartialEq`] since the back links of two equal stacks differ.
+///
+/// The cached lengths are compared first, i.e., the elements of stacks
+/// with different lengths are never compared. Otherwise, both stacks are
+/// walked in a loop from the top to the bottom until two elements differ.
+impl<T: PartialEq, A: Allocator> PartialEq for GenericStack<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for GenericStack<T, A> {}
+
+/// [`GenericStack<T>`] implements trait [`Clone`]: we push clones of the
+/// elements from the bottom to the top on a new stack. We cannot derive
+/// [`Clone`] since the back links must point into the new stack. The nodes
+/// of the clone are allocated with a clone of the allocator, and the clone
+/// has the same maximum depth.
+///
+/// The elements are cloned in a loop, i.e., in contrast to a derived
+/// [`Clone`] of recursive nodes, cloning a very deep stack does not
+/// overflow the call stack.
+impl<T: Clone, A: Allocator + Clone> Clone for GenericStack<T, A> {
+    fn clone(&self) -> Self {
+        let mut stack = GenericStack::new_in(self.alloc.clone());
+        stack.max_depth = self.max_depth;
+        stack.extend(self.iter().rev().cloned());
+        stack
+    }
 }
 
 /// [`GenericStack<T>`] implements trait [`Display`]: It prints the all
 /// entries of the stack separated by '->'. We use the fact that
 /// [`GenericStack<T>`] implements an iterator and that values stored
-/// in the stack must implement trait [`Display`]: we iterate over all
-/// entries and write them to the provided formatter `f`.
+/// in the stack implement trait [`Display`]: we iterate over all
+/// entries and write them to the provided formatter `f`. Use
+/// [`GenericStack::display_with`] to choose a different separator or
+/// direction.
 ///  
 /// # Example:
 ///
 /// `stack=head->6->4->3->2.`
-impl<T: Debug + PartialEq + Display + Clone> fmt::Display for GenericStack<T> {
+impl<T: Display, A: Allocator> fmt::Display for GenericStack<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "head")?;
-        for v in self.iter() {
-            write!(f, "->{v}")?;
-        }
-        write!(f, ".")?;
-        Ok(())
+        self.display_with("->", Direction::TopToBottom)
+            .decorated(true)
+            .fmt(f)
     }
 }
 
-/// `GenericStack<T>` uses a linked list to implement the stack.
-/// The next pointer is of type [`Link<T>`].
-///
-type Link<T> = Option<Box<Node<T>>>;
-
-#[derive(Debug, PartialEq, Clone)]
-struct Node<T: Debug> {
-    element: T,
-    next: Link<T>,
-}
-
-impl<T: Debug + PartialEq + Clone + Display> Stack<T> for GenericStack<T> {
-    /// Create a new monomorphic stack storing elements of type `<T>`.
+impl<T> GenericStack<T> {
+    /// Create a new, empty stack storing elements of type `<T>`. The nodes
+    /// are allocated with the [`Global`] allocator.
+    ///
     /// # Example
     ///
     /// ```
-    /// // We need to import this trait to use the methods of this trait.
-    /// // We can import an implementation like `ll_stack`
-    /// use stack_trait::Stack;
     /// use ll_stack::GenericStack;
-    /// // We create a stack of u128
-    /// let mut stack : GenericStack<u128> = GenericStack::new();
+    /// // `Vec<u8>` does not implement `Display`
+    /// let mut stack: GenericStack<Vec<u8>> = GenericStack::new();
+    /// stack.push(vec![1, 2]);
+    /// assert_eq!(stack.pop(), Some(vec![1, 2]));
     /// ```
-    fn new() -> Self {
-        GenericStack { head: None }
+    #[must_use]
+    pub fn new() -> Self {
+        GenericStack::new_in(Global)
     }
 
-    /// push a new element on the top element of the stack.
-    ///
-    /// # Arguments
-    ///  - `element` to be pushed on the stack
+    /// Create a new stack with clones of the elements of `elements`. The
+    /// elements are pushed in the order of the slice, i.e., the first
+    /// element of the slice is the bottom and the last element becomes the
+    /// top of the stack.
     ///
     /// # Example
     ///
     /// ```
-    /// // We need to import this trait to use the methods of this trait.
-    /// // We can import an implementation like `ll_stack`
-    /// use stack_trait::Stack;
     /// use ll_stack::GenericStack;
-    /// // We create a stack of u64
-    /// let mut stack = GenericStack::new();
-    ///
-    /// // we an push an element to the stack
-    /// stack.push(1u64);
-    /// assert_eq!(stack.peek(), Some(&1u64));
+    /// let mut stack = GenericStack::from_slice(&["a", "b", "c"]);
+    /// assert_eq!(stack.pop(), Some("c"));
+    /// assert_eq!(stack.pop(), Some("b"));
     /// ```
-    fn push(&mut self, element: T) {
-        let new_node = Box::new(Node {
-            element,
-            next: self.head.take(),
-        });
+    pub fn from_slice(elements: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        elements.iter().cloned().collect()
+    }
 
-        self.head = Some(new_node);
+    /// Create a new, empty stack with a maximum depth: pushing an element
+    /// with [`GenericStack::push_checked`] fails once the stack contains
+    /// `max_depth` elements. This permits to enforce, e.g., the recursion
+    /// limit of an interpreter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::with_max_depth(1);
+    /// assert_eq!(stack.max_depth(), 1);
+    /// assert!(stack.push_checked("frame").is_ok());
+    /// let error = stack.push_checked("frame").unwrap_err();
+    /// assert_eq!(error.to_string(), "the stack has reached its maximum depth");
+    /// ```
+    #[must_use]
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        let mut stack = GenericStack::new();
+        stack.max_depth = max_depth;
+        stack
     }
 
-    /// Returns the top element of the stack if it exists, i.e.,
-    /// the last element that was pushed on the stack and not yet
-    /// removed by a preceding call to `pop`
+    /// Moves all elements of `other` on the top of this stack and leaves
+    /// `other` empty. The top element of `other` becomes the top element of
+    /// this stack, and the bottom element of `other` ends up directly above
+    /// the old top of this stack, i.e., this is the same as pushing the
+    /// elements of `other` from its bottom to its top.
     ///
-    /// # Arguments
-    ///  - `pop` does not take any arguments.
+    /// This is O(1) since we only relink the bottom node of `other`. Since
+    /// the nodes of `other` are deallocated by this stack afterwards, this
+    /// is only available for stacks using the [`Global`] allocator.
     ///
     /// # Example
     ///
     /// ```
-    /// // We need to import this trait to use the methods of this trait.
-    /// // We can import an implementation like `ll_stack`
-    /// use stack_trait::Stack;
     /// use ll_stack::GenericStack;
-    /// // We create a stack of i32
-    /// let mut stack = GenericStack::new();
+    /// let mut stack = GenericStack::from(vec![1, 2]);
+    /// let mut other = GenericStack::from(vec![3, 4]);
+    /// stack.append(&mut other);
+    /// assert!(other.is_empty());
+    /// assert_eq!(Vec::from(stack), vec![1, 2, 3, 4]);
+    /// ```
+    pub fn append(&mut self, other: &mut GenericStack<T>) {
+        let (Some(other_head), Some(other_tail)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+
+        // the old head of this stack (if any) now sits below the bottom node of `other`
+        match self.head {
+            // SAFETY: both nodes are owned by the stacks, which we borrow mutably
+            Some(head) => unsafe {
+                (*head.as_ptr()).prev = Some(other_tail);
+                (*other_tail.as_ptr()).next = Some(head);
+            },
+            None => self.tail = Some(other_tail),
+        }
+
+        self.head = Some(other_head);
+        self.len += other.len;
+        trace!("append({}): length {}", other.len, self.len);
+        #[cfg(feature = "stats")]
+        {
+            self.record_moved(&mut other.stats, other.len);
+            self.record_depth();
+        }
+        other.len = 0;
+    }
+}
+
+/// The core operations of [`GenericStack<T>`] do not put any bounds on
+/// `T`, i.e., we can also store values that do not implement [`Display`]
+/// (like `Vec<u8>`). The methods of trait [`Stack`] are implemented
+/// with the help of these methods.
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Create a new, empty stack whose nodes are allocated with `alloc`.
     ///
-    /// // Initially, the stack is empty:
-    /// assert_eq!(stack.pop(), None);
-    /// // we an push an element to the stack
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::{GenericStack, Global};
+    /// let mut stack = GenericStack::new_in(Global);
     /// stack.push(1);
     /// assert_eq!(stack.pop(), Some(1));
     /// ```
-    fn pop(&mut self) -> Option<T> {
-        self.head.take().map(|node| {
-            self.head = node.next;
-            node.element
-        })
+    pub fn new_in(alloc: A) -> Self {
+        GenericStack {
+            head: None,
+            tail: None,
+            len: 0,
+            pool: None,
+            pool_len: 0,
+            max_depth: usize::MAX,
+            alloc,
+            #[cfg(feature = "stats")]
+            stats: Stats::default(),
+            marker: PhantomData,
+        }
     }
 
-    /// borrows the top element of the stack if the stack is not empty.
-    /// This will return `None` if the stack is empty.
+    /// Returns a reference to the allocator of the stack.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// push a new element on the top element of the stack.
+    pub fn push(&mut self, element: T) {
+        let node = self.allocate_node(element);
+        self.push_node(node);
+        trace!("push: length {}", self.len);
+    }
+
+    /// pushes `element` below the bottom element of the stack, i.e., it
+    /// becomes the oldest element and is popped last. Since the stack knows
+    /// its bottom node, this is O(1). Together with [`GenericStack::pop`],
+    /// this turns the stack into a FIFO queue.
     ///
     /// # Example
     ///
     /// ```
-    /// use stack_trait::Stack;
     /// use ll_stack::GenericStack;
-    /// // We create a stack of u128
-    /// let mut stack : GenericStack<u128> = GenericStack::new();
-    ///     println!("Top element: {:?}", stack.peek());
+    /// let mut queue = GenericStack::new();
+    /// queue.push_bottom(1);
+    /// queue.push_bottom(2);
+    /// assert_eq!(queue.pop(), Some(1));
+    /// assert_eq!(queue.pop(), Some(2));
     /// ```
-    fn peek(&self) -> Option<&T> {
-        self.head.as_ref().map(|node| &node.element)
+    pub fn push_bottom(&mut self, element: T) {
+        let node = self.allocate_node(element);
+        // SAFETY: the node was allocated by this stack and is not linked
+        // yet, and the tail (if any) is linked into this stack
+        unsafe { self.link_node_below(node, self.tail) };
+        trace!("push_bottom: length {}", self.len);
     }
 
-    /// borrows the top element of the stack as a mutable value if the stack is not empty.
-    /// This will return `None` if the stack is empty.
+    /// inserts `element` `n` positions below the top of the stack, i.e.,
+    /// afterwards `get(n)` returns the element. `insert(0, element)` is the
+    /// same as `push(element)`, and `insert(len, element)` is the same as
+    /// `push_bottom(element)`. We walk from the closer end of the stack to
+    /// the position, i.e., this takes O(min(n, len - n)) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the stack.
     ///
     /// # Example
     ///
     /// ```
-    /// use stack_trait::Stack;
     /// use ll_stack::GenericStack;
-    /// // We create a stack of u128
-    /// let mut stack : GenericStack<u128> = GenericStack::new();
-    ///   stack.peek_mut().map(|value| { *value += 1; } );
+    /// let mut stack = GenericStack::from(vec!["a", "c"]);
+    /// stack.insert(1, "b");
+    /// assert_eq!(stack.get(1), Some(&"b"));
+    /// assert_eq!(Vec::from(stack), vec!["a", "b", "c"]);
     /// ```
-    fn peek_mut(&mut self) -> Option<&mut T> {
-        self.head.as_mut().map(|node| &mut node.element)
+    pub fn insert(&mut self, n: usize, element: T) {
+        assert!(
+            n <= self.len,
+            "insertion depth {n} is greater than the length {}",
+            self.len
+        );
+        let above = match n {
+            0 => None,
+            _ => self.node_at(n - 1),
+        };
+        let node = self.allocate_node(element);
+        // SAFETY: the node was allocated by this stack and is not linked
+        // yet, and `above` (if any) is linked into this stack
+        unsafe { self.link_node_below(node, above) };
+        trace!("insert({n}): length {}", self.len);
     }
-}
 
-///
-/// We define trait Iterators to define a three iterators for
-/// [`GenericStack`]:
-///
-///  - `iter`:
-///  - `iter_mut`:
-///  - `into_iter`:
-pub trait Iterators<T: Debug + PartialEq + Clone + Display>:
-    Debug + Display + Clone + PartialEq
-{
-    fn into_iter(self) -> IntoIter<T>;
-
-    /// iterator for `ll_stack<T>`
-    fn iter(&self) -> Iter<'_, T>;
+    /// Returns the maximum depth of the stack, i.e., the number of elements
+    /// accepted by [`GenericStack::push_checked`]. The maximum depth of a
+    /// stack that was not created with [`GenericStack::with_max_depth`] is
+    /// `usize::MAX`.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
 
-    /// mutable iterator for `ll_stack<T>`
-    fn iter_mut(&mut self) -> IterMut<'_, T>;
-}
+    /// pushes `element` on the top of the stack if the stack contains less
+    /// than [`max_depth`](GenericStack::max_depth) elements.
+    ///
+    /// Only this method enforces the maximum depth: [`GenericStack::push`]
+    /// and the other methods adding elements always succeed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `element` in a [`StackFullError`] if the stack is full. The
+    /// stack is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::{GenericStack, StackFullError};
+    /// let mut stack = GenericStack::with_max_depth(2);
+    /// assert_eq!(stack.push_checked(1), Ok(()));
+    /// assert_eq!(stack.push_checked(2), Ok(()));
+    /// assert_eq!(stack.push_checked(3), Err(StackFullError(3)));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.push_checked(3), Ok(()));
+    /// ```
+    pub fn push_checked(&mut self, element: T) -> Result<(), StackFullError<T>> {
+        if self.len >= self.max_depth {
+            return Err(StackFullError(element));
+        }
+        self.push(element);
+        Ok(())
+    }
 
-impl<T: Debug + PartialEq + Clone + Display> Iterators<T> for GenericStack<T> {
-    fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        let node = self.pop_node()?;
+        trace!("pop: length {}", self.len);
+        // SAFETY: the node was allocated by this stack and is unlinked
+        Some(unsafe { self.free_node(node) })
     }
 
-    /// the iterator starts with the head element and method `next()`
-    /// will then follow the next pointers.
-    fn iter(&self) -> Iter<'_, T> {
-        Iter {
-            next: self.head.as_deref(),
+    /// Removes and returns the top element of the stack if the stack is not
+    /// empty and `f` returns `true` for the top element. Otherwise, the
+    /// stack is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut brackets = GenericStack::from(vec!['(', '[']);
+    /// assert_eq!(brackets.pop_if(|&open| open == '('), None);
+    /// assert_eq!(brackets.pop_if(|&open| open == '['), Some('['));
+    /// assert_eq!(brackets.len(), 1);
+    /// ```
+    pub fn pop_if(&mut self, f: impl FnOnce(&T) -> bool) -> Option<T> {
+        if f(self.peek()?) {
+            self.pop()
+        } else {
+            None
         }
     }
 
-    fn iter_mut(&mut self) -> IterMut<'_, T> {
-        IterMut {
-            next: self.head.as_deref_mut(),
+    /// replaces the top element of the stack by `value` and returns the
+    /// old top element. The node of the top element is reused, i.e., this
+    /// does not allocate. If the stack is empty, `value` is pushed and
+    /// `None` is returned, like [`Option::replace`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// assert_eq!(stack.replace_top(1), None);
+    /// assert_eq!(stack.replace_top(2), Some(1));
+    /// assert_eq!(Vec::from(stack), vec![2]);
+    /// ```
+    pub fn replace_top(&mut self, value: T) -> Option<T> {
+        if let Some(top) = self.peek_mut() {
+            Some(mem::replace(top, value))
+        } else {
+            self.push(value);
+            None
         }
     }
-}
 
-pub struct IntoIter<T: Debug + PartialEq + Clone + Display>(GenericStack<T>);
+    /// moves the top element out of the stack and leaves `T::default()` in
+    /// its place, i.e., the length of the stack does not change. This
+    /// returns `None` if the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut frames = GenericStack::from(vec![vec![1, 2]]);
+    /// assert_eq!(frames.take_top(), Some(vec![1, 2]));
+    /// assert_eq!(frames.peek(), Some(&vec![]));
+    /// ```
+    pub fn take_top(&mut self) -> Option<T>
+    where
+        T: Default,
+    {
+        self.peek_mut().map(mem::take)
+    }
 
-impl<T: Debug + PartialEq + Clone + Display> Iterator for IntoIter<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
-        // access fields of a tuple struct numerically
-        self.0.pop()
+    /// Removes and returns the element `n` positions below the top of the
+    /// stack, i.e., `remove(0)` is the same as `pop()`. This will return
+    /// `None` (and not change the stack) if the stack contains `n` or less
+    /// elements. Like [`GenericStack::insert`], this takes O(min(n, len -
+    /// n)) time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.remove(1), Some(2));
+    /// assert_eq!(stack.remove(2), None);
+    /// assert_eq!(Vec::from(stack), vec![1, 3]);
+    /// ```
+    pub fn remove(&mut self, n: usize) -> Option<T> {
+        let node = self.node_at(n)?;
+        // SAFETY: the node is linked into this stack and is recycled once
+        // it is unlinked
+        let element = unsafe {
+            self.unlink_node(node);
+            self.free_node(node)
+        };
+        trace!("remove({n}): length {}", self.len);
+        Some(element)
     }
-}
 
-pub struct Iter<'a, T: Debug> {
-    next: Option<&'a Node<T>>,
-}
+    /// Removes up to `n` elements from the top of the stack and returns them
+    /// in the order in which `pop` would have returned them, i.e., the old
+    /// top element is the first element of the returned vector. If the stack
+    /// contains less than `n` elements, all elements are returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=5).collect();
+    /// assert_eq!(stack.pop_n(2), vec![5, 4]);
+    /// assert_eq!(stack.pop_n(10), vec![3, 2, 1]);
+    /// assert!(stack.is_empty());
+    /// ```
+    pub fn pop_n(&mut self, n: usize) -> Vec<T> {
+        let count = n.min(self.len);
+        let mut elements = Vec::with_capacity(count);
+        let mut link = self.head;
+        for _ in 0..count {
+            let Some(node) = link else { break };
+            // SAFETY: the node was allocated by this stack, and we unlink
+            // all freed nodes below by moving the head.
+            unsafe {
+                link = (*node.as_ptr()).next;
+                elements.push(self.free_node(node));
+            }
+        }
 
-impl<'a, T: Debug + PartialEq + Clone + Display> Iterator for Iter<'a, T> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next.map(|node| {
-            self.next = node.next.as_deref();
-            &node.element
-        })
+        self.head = link;
+        match self.head {
+            // SAFETY: the new head is a node of this stack
+            Some(head) => unsafe { (*head.as_ptr()).prev = None },
+            None => self.tail = None,
+        }
+        // we update the length only once
+        self.len -= count;
+        trace!("pop_n({n}): length {}", self.len);
+        elements
     }
-}
 
-pub struct IterMut<'a, T: Debug> {
-    next: Option<&'a mut Node<T>>,
-}
+    /// Pushes all items of `iter` on the stack in a single pass, in the
+    /// order in which they are yielded, i.e., the last item ends up on the
+    /// top of the stack. Popping the items with [`GenericStack::pop_n`]
+    /// returns them in reverse order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// stack.push_all(vec![1, 2, 3]);
+    /// assert_eq!(stack.peek(), Some(&3));
+    /// assert_eq!(stack.pop_n(3), vec![3, 2, 1]);
+    /// ```
+    pub fn push_all(&mut self, iter: impl IntoIterator<Item = T>) {
+        for element in iter {
+            self.push(element);
+        }
+    }
 
-impl<'a, T: Debug + PartialEq> Iterator for IterMut<'a, T> {
-    type Item = &'a mut T;
+    /// creates an unlinked node storing `element`: we reuse a node of the
+    /// pool if possible and allocate a new node otherwise.
+    fn allocate_node(&mut self, element: T) -> NonNull<Node<T>> {
+        let node = match self.pool {
+            Some(node) => {
+                // SAFETY: the `next` links of pooled nodes are initialized
+                self.pool = unsafe { (*node.as_ptr()).next };
+                self.pool_len -= 1;
+                node
+            }
+            None => self.allocate_uninit_node(),
+        };
+        #[cfg(feature = "stats")]
+        {
+            self.stats.pushes += 1;
+        }
+        // SAFETY: the memory is valid for writes of a `Node<T>`
+        unsafe {
+            node.as_ptr().write(Node {
+                element,
+                next: None,
+                prev: None,
+            });
+        };
+        node
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next.take().map(|node| {
-            self.next = node.next.as_deref_mut();
-            &mut node.element
-        })
+    /// allocates the memory of a node with the allocator of the stack.
+    /// Nothing is initialized.
+    fn allocate_uninit_node(&self) -> NonNull<Node<T>> {
+        let layout = Layout::new::<Node<T>>();
+        match self.alloc.allocate(layout) {
+            Ok(memory) => memory.cast::<Node<T>>(),
+            Err(_) => handle_alloc_error(layout),
+        }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// moves the element out of an unlinked node and puts the node in the
+    /// pool.
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been allocated by [`GenericStack::allocate_node`]
+    /// of this stack and must no longer be linked into the stack.
+    unsafe fn free_node(&mut self, node: NonNull<Node<T>>) -> T {
+        let element = ptr::addr_of!((*node.as_ptr()).element).read();
+        self.pool_node(node);
+        #[cfg(feature = "stats")]
+        {
+            self.stats.pops += 1;
+        }
+        element
+    }
 
-    #[test]
-    fn basics() {
-        let mut stack = GenericStack::new();
+    /// puts an unlinked node in the pool and drops its element in place.
+    /// The node is pooled first, since the element counts as dropped even
+    /// if its drop panics.
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been allocated by the allocator of this stack, must
+    /// not be linked into the stack, and its element must be initialized.
+    unsafe fn drop_node(&mut self, node: NonNull<Node<T>>) {
+        self.pool_node(node);
+        #[cfg(feature = "stats")]
+        {
+            self.stats.pops += 1;
+        }
+        ptr::drop_in_place(ptr::addr_of_mut!((*node.as_ptr()).element));
+    }
 
-        // Check empty stack behaves right
-        assert_eq!(stack.pop(), None);
+    /// puts a node without an element in the pool.
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been allocated by the allocator of this stack and
+    /// must not be linked into the stack.
+    unsafe fn pool_node(&mut self, node: NonNull<Node<T>>) {
+        ptr::addr_of_mut!((*node.as_ptr()).next).write(self.pool);
+        self.pool = Some(node);
+        self.pool_len += 1;
+    }
 
-        // Populate stack
-        stack.push(1);
-        stack.push(2);
-        stack.push(3);
+    /// pushes an unlinked node on the top of the stack and updates the
+    /// back links. Operations that relink nodes (instead of moving the
+    /// elements) are implemented with `push_node` and `pop_node`.
+    fn push_node(&mut self, node: NonNull<Node<T>>) {
+        // SAFETY: `node` is an unlinked node of this stack
+        unsafe { self.link_node_below(node, None) };
+    }
 
-        // Check normal removal
-        assert_eq!(stack.pop(), Some(3));
-        assert_eq!(stack.pop(), Some(2));
+    /// links an unlinked node directly below the node `above`, or on the
+    /// top of the stack if `above` is `None`, and updates the links of its
+    /// new neighbors (or the head and the tail).
+    ///
+    /// # Safety
+    ///
+    /// `node` must have been allocated by this stack and must not be
+    /// linked, and `above` (if any) must be linked into this stack.
+    unsafe fn link_node_below(&mut self, node: NonNull<Node<T>>, above: Link<T>) {
+        let below = match above {
+            Some(above) => (*above.as_ptr()).next,
+            None => self.head,
+        };
+        (*node.as_ptr()).prev = above;
+        (*node.as_ptr()).next = below;
+        match above {
+            Some(above) => (*above.as_ptr()).next = Some(node),
+            None => self.head = Some(node),
+        }
+        match below {
+            Some(below) => (*below.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.len += 1;
+        #[cfg(feature = "stats")]
+        self.record_depth();
+    }
+
+    /// unlinks the top node of the stack and returns it.
+    fn pop_node(&mut self) -> Option<NonNull<Node<T>>> {
+        // SAFETY: the head is a node of this stack
+        self.head.inspect(|&node| unsafe { self.unlink_node(node) })
+    }
+
+    /// returns the node `depth` positions below the top of the stack. We
+    /// walk from the top or from the bottom, whichever is closer.
+    fn node_at(&self, depth: usize) -> Link<T> {
+        if depth >= self.len {
+            return None;
+        }
+        // SAFETY: the nodes are owned by this stack
+        unsafe {
+            if depth < self.len / 2 {
+                let mut node = self.head?;
+                for _ in 0..depth {
+                    node = (*node.as_ptr()).next?;
+                }
+                Some(node)
+            } else {
+                let mut node = self.tail?;
+                for _ in depth + 1..self.len {
+                    node = (*node.as_ptr()).prev?;
+                }
+                Some(node)
+            }
+        }
+    }
+
+    /// unlinks the bottom node of the stack and returns it.
+    fn pop_bottom_node(&mut self) -> Option<NonNull<Node<T>>> {
+        // SAFETY: the tail is a node of this stack
+        self.tail.inspect(|&node| unsafe { self.unlink_node(node) })
+    }
+
+    /// unlinks an arbitrary node of the stack and updates the links of its
+    /// neighbors (or the head and the tail).
+    ///
+    /// # Safety
+    ///
+    /// `node` must be linked into this stack.
+    unsafe fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        let node = &mut *node.as_ptr();
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        node.next = None;
+        node.prev = None;
+        self.len -= 1;
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: the head is owned by this stack and lives as long as `&self`
+        self.head.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the head is owned by this stack, which we borrow mutably
+        self.head
+            .map(|node| unsafe { &mut (*node.as_ptr()).element })
+    }
+
+    /// borrows the top element of the stack as a mutable value, pushing
+    /// `default` first if the stack is empty. Like the entry API of a map,
+    /// this replaces checking for an empty stack before calling
+    /// [`GenericStack::peek_mut`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut sums = GenericStack::new();
+    /// *sums.peek_or_push(0) += 5;
+    /// *sums.peek_or_push(0) += 2;
+    /// assert_eq!(sums.pop(), Some(7));
+    /// ```
+    pub fn peek_or_push(&mut self, default: T) -> &mut T {
+        self.peek_or_push_with(|| default)
+    }
+
+    /// borrows the top element of the stack as a mutable value, pushing
+    /// the result of `f` first if the stack is empty. `f` is only called if
+    /// the stack is empty.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn peek_or_push_with(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        if self.head.is_none() {
+            self.push(f());
+        }
+        self.peek_mut()
+            .expect("the stack is not empty after a push")
+    }
+
+    /// borrows the bottom element of the stack, i.e., the oldest element,
+    /// if the stack is not empty. Since the stack knows its bottom node,
+    /// this is O(1).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.peek_bottom(), Some(&1));
+    /// assert_eq!(stack.peek(), Some(&3));
+    /// ```
+    pub fn peek_bottom(&self) -> Option<&T> {
+        // SAFETY: the tail is owned by this stack and lives as long as `&self`
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// borrows the bottom element of the stack as a mutable value if the
+    /// stack is not empty.
+    pub fn peek_bottom_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: the tail is owned by this stack, which we borrow mutably
+        self.tail
+            .map(|node| unsafe { &mut (*node.as_ptr()).element })
+    }
+
+    /// borrows the element `n` positions below the top of the stack, i.e.,
+    /// `get(0)` is the same as `peek()`. This will return `None` if the
+    /// stack contains `n` or less elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=3).collect();
+    /// assert_eq!(stack.get(0), Some(&3));
+    /// assert_eq!(stack.get(2), Some(&1));
+    /// assert_eq!(stack.get(3), None);
+    /// ```
+    pub fn get(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// borrows the element `n` positions below the top of the stack as a
+    /// mutable value, i.e., `get_mut(0)` is the same as `peek_mut()`. This
+    /// will return `None` if the stack contains `n` or less elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// if let Some(value) = stack.get_mut(2) {
+    ///     *value = 10;
+    /// }
+    /// assert_eq!(stack.get(2), Some(&10));
+    /// ```
+    pub fn get_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.iter_mut().nth(n)
+    }
+
+    /// iterator for `GenericStack<T>`: the iterator starts with the head
+    /// element and method `next()` will then follow the next pointers.
+    ///
+    /// [`Iter`] implements [`DoubleEndedIterator`], i.e., `iter().rev()`
+    /// walks the stack from the bottom to the top (insertion order).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=3).collect();
+    /// assert_eq!(stack.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+    /// assert_eq!(stack.iter().rev().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            back: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// iterator over the elements together with their depth, i.e., their
+    /// distance from the top: the top element has depth 0. Since [`Iter`]
+    /// knows its length, the depths are also correct when iterating from the
+    /// bottom with `rev()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec!['a', 'b', 'c']);
+    /// let mut iter = stack.iter_with_depth();
+    /// assert_eq!(iter.next(), Some((0, &'c')));
+    /// assert_eq!(iter.next_back(), Some((2, &'a')));
+    /// for (depth, element) in stack.iter_with_depth() {
+    ///     println!("{depth}: {element}");
+    /// }
+    /// ```
+    pub fn iter_with_depth(&self) -> Enumerate<Iter<'_, T>> {
+        self.iter().enumerate()
+    }
+
+    /// iterator over the elements of this stack and `other` in lockstep,
+    /// aligned at the top: the first pair consists of both top elements.
+    /// The iterator stops at the bottom of the shorter stack. Since both
+    /// iterators know their length, `rev()` starts with the pair at the
+    /// depth of the bottom element of the shorter stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let values = GenericStack::from(vec![1, 2, 3]);
+    /// let types = GenericStack::from(vec!["int", "int"]);
+    /// let pairs: Vec<_> = values.zip(&types).collect();
+    /// assert_eq!(pairs, vec![(&3, &"int"), (&2, &"int")]);
+    /// ```
+    pub fn zip<'a, U, B: Allocator>(
+        &'a self,
+        other: &'a GenericStack<U, B>,
+    ) -> Zip<Iter<'a, T>, Iter<'a, U>> {
+        self.iter().zip(other.iter())
+    }
+
+    /// mutable iterator for `GenericStack<T>`: like [`Iter`], [`IterMut`]
+    /// implements [`DoubleEndedIterator`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// for (i, value) in stack.iter_mut().rev().enumerate() {
+    ///     *value += 10 * i;
+    /// }
+    /// assert_eq!(Vec::from(stack), vec![1, 12, 23]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            back: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Removes the elements from the top to the bottom and returns them by
+    /// value. In contrast to `into_iter`, the stack is only borrowed: when
+    /// the [`Drain`] is dropped, all remaining elements are removed and the
+    /// stack is empty but can be used again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// assert_eq!(stack.drain().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// assert!(stack.is_empty());
+    /// stack.push(4);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        Drain { stack: self }
+    }
+
+    /// Converts the stack into a vector in the order of `pop`: the top
+    /// element of the stack becomes the first element of the vector, and
+    /// the bottom element becomes the last element. This order is part of
+    /// the contract of this method.
+    ///
+    /// Note that `Vec::from(stack)` uses the opposite order, i.e., the
+    /// order in which the elements were pushed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.clone().into_vec(), vec![3, 2, 1]);
+    /// assert_eq!(Vec::from(stack), vec![1, 2, 3]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Clones the elements of the stack into a vector, top element first,
+    /// i.e., this returns the same vector as [`GenericStack::into_vec`]
+    /// without consuming the stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.to_vec(), vec![3, 2, 1]);
+    /// assert_eq!(stack.len(), 3);
+    /// ```
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Returns the number of elements stored on the stack.
+    /// The length is cached in the stack, i.e., this is O(1) and
+    /// does not need to walk the linked list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// assert_eq!(stack.len(), 0);
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// assert!(stack.is_empty());
+    /// stack.push(1);
+    /// assert!(!stack.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the stack can hold without
+    /// allocating, i.e., the length of the stack plus the number of nodes
+    /// in the node pool.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// stack.pop();
+    /// assert_eq!(stack.len(), 2);
+    /// assert_eq!(stack.capacity(), 3);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.len + self.pool_len
+    }
+
+    /// Makes sure that the node pool contains at least `additional` nodes,
+    /// i.e., the next `additional` pushes do not allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// stack.push(1);
+    /// stack.reserve(10);
+    /// assert_eq!(stack.capacity(), 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        for _ in self.pool_len..additional {
+            let node = self.allocate_uninit_node();
+            // SAFETY: the node was just allocated by the allocator of this stack
+            unsafe { self.pool_node(node) };
+        }
+    }
+
+    /// Deallocates all nodes of the node pool, i.e., afterwards the
+    /// capacity of the stack is equal to its length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// stack.clear();
+    /// assert_eq!(stack.capacity(), 3);
+    /// stack.shrink_pool();
+    /// assert_eq!(stack.capacity(), 0);
+    /// ```
+    pub fn shrink_pool(&mut self) {
+        while let Some(node) = self.pool {
+            // SAFETY: pooled nodes were allocated by the allocator of this
+            // stack, and their `next` links are initialized.
+            unsafe {
+                self.pool = (*node.as_ptr()).next;
+                self.alloc.deallocate(node.cast(), Layout::new::<Node<T>>());
+            }
+        }
+        self.pool_len = 0;
+    }
+
+    /// Reverses the order of the elements in place: the bottom element
+    /// becomes the top element and vice versa. The nodes are relinked,
+    /// i.e., no elements are moved or cloned and nothing is allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// stack.reverse();
+    /// assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn reverse(&mut self) {
+        let mut link = self.head;
+        while let Some(node) = link {
+            // SAFETY: the node is owned by this stack, which we borrow mutably
+            let node = unsafe { &mut *node.as_ptr() };
+            mem::swap(&mut node.next, &mut node.prev);
+            // the old next link is now stored in `prev`
+            link = node.prev;
+        }
+        mem::swap(&mut self.head, &mut self.tail);
+        trace!("reverse: length {}", self.len);
+    }
+
+    /// Exchanges the top two elements of the stack (`swap` in Forth). The
+    /// nodes are relinked, i.e., the elements are neither moved nor cloned.
+    /// Returns `false` and leaves the stack unchanged if the stack contains
+    /// less than two elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=3).collect();
+    /// assert!(stack.swap_top());
+    /// assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![2, 3, 1]);
+    /// ```
+    pub fn swap_top(&mut self) -> bool {
+        if self.len < 2 {
+            return false;
+        }
+        if let (Some(first), Some(second)) = (self.pop_node(), self.pop_node()) {
+            self.push_node(first);
+            self.push_node(second);
+        }
+        trace!("swap_top: length {}", self.len);
+        true
+    }
+
+    /// Rotates the top three elements of the stack (`rot` in Forth): the
+    /// third element comes to the top, i.e., `a b c` (with `c` on top)
+    /// becomes `b c a` (with `a` on top). The nodes are relinked, i.e., the
+    /// elements are neither moved nor cloned. Returns `false` and leaves the
+    /// stack unchanged if the stack contains less than three elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert!(stack.rot());
+    /// assert_eq!(Vec::from(stack), vec![2, 3, 1]);
+    /// ```
+    pub fn rot(&mut self) -> bool {
+        if self.len < 3 {
+            return false;
+        }
+        if let (Some(first), Some(second), Some(third)) =
+            (self.pop_node(), self.pop_node(), self.pop_node())
+        {
+            self.push_node(second);
+            self.push_node(first);
+            self.push_node(third);
+        }
+        trace!("rot: length {}", self.len);
+        true
+    }
+
+    /// Moves the top `n` elements to the bottom of the stack and keeps their
+    /// order, i.e., the stack is rotated like a circular buffer: `a b c d`
+    /// (with `d` on top) becomes `c d a b` (with `b` on top) for `n = 2`.
+    /// The two parts of the stack are spliced, i.e., this takes O(min(n,
+    /// len - n)) time to find the split and does not move any elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 3, 4, 5]);
+    /// stack.rotate_up(2);
+    /// assert_eq!(Vec::from(stack), vec![4, 5, 1, 2, 3]);
+    /// ```
+    pub fn rotate_up(&mut self, n: usize) {
+        assert!(
+            n <= self.len,
+            "cannot rotate {n} elements of a stack of length {}",
+            self.len
+        );
+        if n == 0 || n == self.len {
+            return;
+        }
+        let (Some(head), Some(tail)) = (self.head, self.tail) else {
+            return;
+        };
+        let last = self.node_at(n - 1).expect("n is less than the length");
+        // SAFETY: all nodes are owned by this stack, and `last` is neither
+        // the head nor the tail of the stack since `0 < n < len`
+        unsafe {
+            let first = (*last.as_ptr()).next.expect("n is less than the length");
+            // the old top part ends below the old bottom
+            (*tail.as_ptr()).next = Some(head);
+            (*head.as_ptr()).prev = Some(tail);
+            (*last.as_ptr()).next = None;
+            (*first.as_ptr()).prev = None;
+            self.head = Some(first);
+            self.tail = Some(last);
+        }
+        trace!("rotate_up({n}): length {}", self.len);
+    }
+
+    /// Moves the bottom `n` elements to the top of the stack and keeps their
+    /// order, i.e., this is the inverse of [`GenericStack::rotate_up`]:
+    /// `a b c d` (with `d` on top) becomes `c d a b` (with `b` on top) for
+    /// `n = 2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 3, 4, 5]);
+    /// stack.rotate_down(2);
+    /// assert_eq!(Vec::from(stack), vec![3, 4, 5, 1, 2]);
+    /// ```
+    pub fn rotate_down(&mut self, n: usize) {
+        assert!(
+            n <= self.len,
+            "cannot rotate {n} elements of a stack of length {}",
+            self.len
+        );
+        self.rotate_up(self.len - n);
+    }
+
+    /// Pushes a clone of the top element on the stack (`dup` in Forth).
+    /// Returns `false` and leaves the stack unchanged if the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// assert!(!stack.dup());
+    /// stack.push(1);
+    /// assert!(stack.dup());
+    /// assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![1, 1]);
+    /// ```
+    pub fn dup(&mut self) -> bool
+    where
+        T: Clone,
+    {
+        match self.peek().cloned() {
+            Some(top) => {
+                self.push(top);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the stack contains an element equal to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=3).collect();
+    /// assert!(stack.contains(&2));
+    /// assert!(!stack.contains(&4));
+    /// ```
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|element| element == value)
+    }
+
+    /// Returns the depth of the topmost element for which `f` returns
+    /// `true`, i.e., its distance from the top as used by
+    /// [`GenericStack::get`], [`GenericStack::insert`], and
+    /// [`GenericStack::remove`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 2, 3, 4]);
+    /// let depth = stack.position(|v| v % 2 == 1);
+    /// assert_eq!(depth, Some(1));
+    /// assert_eq!(stack.remove(depth.unwrap()), Some(3));
+    /// assert_eq!(stack.position(|v| *v > 4), None);
+    /// ```
+    pub fn position(&self, f: impl FnMut(&T) -> bool) -> Option<usize> {
+        self.iter().position(f)
+    }
+
+    /// borrows the topmost element for which `f` returns `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![("x", 1), ("y", 2), ("x", 3)]);
+    /// assert_eq!(stack.find(|(name, _)| *name == "x"), Some(&("x", 3)));
+    /// assert_eq!(stack.find(|(name, _)| *name == "z"), None);
+    /// ```
+    pub fn find(&self, mut f: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.iter().find(|element| f(element))
+    }
+
+    /// Retains only the elements for which the predicate `f` returns `true`
+    /// and drops all other elements. The relative order of the retained
+    /// elements is preserved. The predicate is called exactly once for each
+    /// element, from the top to the bottom.
+    ///
+    /// The nodes are relinked, i.e., no elements are moved or cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<_> = (1..=6).collect();
+    /// stack.retain(|v| v % 2 == 0);
+    /// assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![6, 4, 2]);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let mut link = self.head;
+        while let Some(node) = link {
+            // SAFETY: the node is owned by this stack. We read its next link
+            // before the node is unlinked and deallocated.
+            unsafe {
+                link = (*node.as_ptr()).next;
+                if !f(&(*node.as_ptr()).element) {
+                    self.unlink_node(node);
+                    drop(self.free_node(node));
+                }
+            }
+        }
+        trace!("retain: length {}", self.len);
+    }
+
+    /// Converts the stack into a stack of type `U` by applying `f` to each
+    /// element. The order of the elements is preserved, i.e., the top
+    /// element of the result is `f` applied to the top element of this
+    /// stack. `f` is called from the top to the bottom, and the elements
+    /// are appended at the bottom of the new stack with
+    /// [`GenericStack::push_bottom`], i.e., we need neither an
+    /// intermediate vector nor a reversal.
+    ///
+    /// The nodes of the new stack are allocated with a clone of the
+    /// allocator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// let strings = stack.map(|v| v.to_string());
+    /// assert_eq!(Vec::from(strings), vec!["1", "2", "3"]);
+    /// ```
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> GenericStack<U, A>
+    where
+        A: Clone,
+    {
+        let mut mapped = GenericStack::new_in(self.alloc.clone());
+        for element in self {
+            mapped.push_bottom(f(element));
+        }
+        mapped
+    }
+
+    /// Returns a new stack with clones of the elements for which `f`
+    /// returns `true`, in the same relative order as in this stack. `f` is
+    /// called from the top to the bottom, and the clones are appended at
+    /// the bottom of the new stack, i.e., the order is not reversed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=6).collect();
+    /// let even = stack.filtered(|v| v % 2 == 0);
+    /// assert_eq!(Vec::from(even), vec![2, 4, 6]);
+    /// assert_eq!(stack.len(), 6);
+    /// ```
+    #[must_use]
+    pub fn filtered(&self, mut f: impl FnMut(&T) -> bool) -> GenericStack<T, A>
+    where
+        T: Clone,
+        A: Clone,
+    {
+        let mut filtered = GenericStack::new_in(self.alloc.clone());
+        for element in self.iter().filter(|element| f(element)) {
+            filtered.push_bottom(element.clone());
+        }
+        filtered
+    }
+
+    /// Consumes the stack and returns it with only the elements for which
+    /// `f` returns `true`. This is [`GenericStack::retain`] for method
+    /// chains: the nodes of the rejected elements are unlinked, and no
+    /// elements are moved or cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=6).collect();
+    /// assert_eq!(Vec::from(stack.filter(|v| v % 3 == 0)), vec![3, 6]);
+    /// ```
+    #[must_use]
+    pub fn filter(mut self, f: impl FnMut(&T) -> bool) -> Self {
+        self.retain(f);
+        self
+    }
+
+    /// Splits the stack into the elements for which `f` returns `true` and
+    /// the elements for which it returns `false`. Both stacks preserve the
+    /// relative order of their elements. `f` is called exactly once for
+    /// each element, from the top to the bottom.
+    ///
+    /// The nodes are relinked, i.e., no elements are moved or cloned. The
+    /// nodes of the second stack are owned by a clone of the allocator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack: GenericStack<_> = (1..=6).collect();
+    /// let (even, odd) = stack.partition(|v| v % 2 == 0);
+    /// assert_eq!(Vec::from(even), vec![2, 4, 6]);
+    /// assert_eq!(Vec::from(odd), vec![1, 3, 5]);
+    /// ```
+    pub fn partition(mut self, mut f: impl FnMut(&T) -> bool) -> (Self, Self)
+    where
+        A: Clone,
+    {
+        let mut rejected = GenericStack::new_in(self.alloc.clone());
+        let mut link = self.head;
+        while let Some(node) = link {
+            // SAFETY: the node is owned by this stack. We read its next link
+            // before the node is moved to the bottom of `rejected`, whose
+            // allocator is a clone of the allocator of this stack.
+            unsafe {
+                link = (*node.as_ptr()).next;
+                if !f(&(*node.as_ptr()).element) {
+                    self.unlink_node(node);
+                    rejected.link_node_below(node, rejected.tail);
+                }
+            }
+        }
+        #[cfg(feature = "stats")]
+        {
+            rejected.record_moved(&mut self.stats, rejected.len);
+            rejected.record_depth();
+        }
+        trace!("partition({}): length {}", rejected.len, self.len);
+        (self, rejected)
+    }
+
+    /// Removes consecutive equal elements and keeps only the lowest element
+    /// of each run, like [`Vec::dedup`] does for the vector of the stack
+    /// (from the bottom to the top). If the stack is sorted, all duplicates
+    /// are removed.
+    ///
+    /// The nodes of the removed elements are unlinked, i.e., no elements
+    /// are moved or cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 1, 2, 3, 3, 3, 1]);
+    /// stack.dedup();
+    /// assert_eq!(Vec::from(stack), vec![1, 2, 3, 1]);
+    /// ```
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        let Some(mut kept) = self.tail else {
+            return;
+        };
+        // SAFETY: the nodes are owned by this stack. We read the link to
+        // the node above before the node is unlinked and deallocated.
+        unsafe {
+            let mut link = (*kept.as_ptr()).prev;
+            while let Some(node) = link {
+                link = (*node.as_ptr()).prev;
+                if (*node.as_ptr()).element == (*kept.as_ptr()).element {
+                    self.unlink_node(node);
+                    drop(self.free_node(node));
+                } else {
+                    kept = node;
+                }
+            }
+        }
+        trace!("dedup: length {}", self.len);
+    }
+
+    /// Removes all elements from the stack. The nodes are unlinked
+    /// one after the other in a loop, i.e., clearing a very deep stack
+    /// does not recurse. The nodes are kept in the node pool.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// stack.clear();
+    /// assert!(stack.is_empty());
+    /// assert_eq!(stack.peek(), None);
+    /// ```
+    pub fn clear(&mut self) {
+        self.drop_elements();
+        trace!("clear: length 0");
+    }
+
+    /// drops the elements from the top to the bottom and keeps the nodes in
+    /// the node pool. The elements are dropped in place, i.e., the elements
+    /// of a [`PinnedStack`] do not move even if the drop of one of them
+    /// panics and the inner stack drops the remaining ones.
+    fn drop_elements(&mut self) {
+        while let Some(node) = self.pop_node() {
+            // SAFETY: the node was allocated by this stack and is unlinked
+            unsafe { self.drop_node(node) };
+        }
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`Default`]: the default stack
+/// is empty. This permits to use [`GenericStack<T>`] inside of structs
+/// deriving [`Default`] and with [`core::mem::take`].
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack: GenericStack<u32> = GenericStack::default();
+/// stack.push(1);
+/// let old = std::mem::take(&mut stack);
+/// assert!(stack.is_empty());
+/// assert_eq!(old.len(), 1);
+/// ```
+impl<T, A: Allocator + Default> Default for GenericStack<T, A> {
+    fn default() -> Self {
+        GenericStack::new_in(A::default())
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`Hash`] if `T` implements [`Hash`]:
+/// we hash the length of the stack followed by all elements from the
+/// top to the bottom. This is consistent with [`PartialEq`], i.e., equal
+/// stacks have equal hashes.
+impl<T: Hash, A: Allocator> Hash for GenericStack<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for element in self {
+            element.hash(state);
+        }
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`PartialOrd`] if `T` implements
+/// [`PartialOrd`]: stacks are compared lexicographically from the top to
+/// the bottom, i.e., like a `Vec` whose first element is the top of the stack.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let a: GenericStack<_> = vec![1, 2].into_iter().collect();
+/// let b: GenericStack<_> = vec![1, 3].into_iter().collect();
+/// // the top of `a` (2) is smaller than the top of `b` (3)
+/// assert!(a < b);
+/// ```
+impl<T: PartialOrd, A: Allocator> PartialOrd for GenericStack<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`Ord`] if `T` implements [`Ord`]
+/// using the same lexicographic order as [`PartialOrd`].
+impl<T: Ord, A: Allocator> Ord for GenericStack<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`Index`] with the help of
+/// [`GenericStack::get`]: `stack[0]` is the top element and `stack[n]` is
+/// the element `n` positions below the top. Like the index of a `Vec`, it
+/// panics if `n` is out of range. Indexing takes O(n) time.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack: GenericStack<_> = (1..=3).collect();
+/// assert_eq!(stack[0], 3);
+/// stack[2] = 10;
+/// assert_eq!(stack[2], 10);
+/// ```
+impl<T, A: Allocator> Index<usize> for GenericStack<T, A> {
+    type Output = T;
+
+    fn index(&self, n: usize) -> &T {
+        let len = self.len;
+        self.get(n)
+            .unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {n}"))
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`IndexMut`] with the help of
+/// [`GenericStack::get_mut`].
+impl<T, A: Allocator> IndexMut<usize> for GenericStack<T, A> {
+    fn index_mut(&mut self, n: usize) -> &mut T {
+        let len = self.len;
+        self.get_mut(n)
+            .unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {n}"))
+    }
+}
+
+/// [`GenericStack<T>`] implements trait [`Drop`]: the nodes are not owned
+/// by a [`Box`] but allocated with the allocator of the stack. Hence, we
+/// unlink the nodes in a loop like [`GenericStack::clear`], i.e.,
+/// dropping a deep stack does not recurse, and deallocate them with
+/// [`GenericStack::shrink_pool`].
+impl<T, A: Allocator> Drop for GenericStack<T, A> {
+    fn drop(&mut self) {
+        self.drop_elements();
+        self.shrink_pool();
+    }
+}
+
+/// `GenericStack<T>` uses a doubly linked list to implement the stack.
+/// The links are of type [`Link<T>`]: the nodes are allocated with the
+/// allocator of the stack, which owns all of its nodes. The links are only
+/// dereferenced while the stack is borrowed. References to a node (or an
+/// element) are only created from these links and never outlive the
+/// borrow of the stack, i.e., the implementation passes Miri.
+type Link<T> = Option<NonNull<Node<T>>>;
+
+/// Each node points to the node below it (`next`) and back to the node
+/// above it (`prev`), i.e., the node that was pushed after it. The back
+/// links permit to iterate from the bottom to the top.
+struct Node<T> {
+    element: T,
+    next: Link<T>,
+    prev: Link<T>,
+}
+
+#[allow(clippy::missing_fields_in_debug)]
+impl<T: Debug> Debug for Node<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("element", &self.element)
+            // SAFETY: the next node is owned by the same stack as this node
+            .field("next", &self.next.map(|node| unsafe { node.as_ref() }))
+            .finish()
+    }
+}
+
+// SAFETY: the links are raw pointers into nodes owned by the same stack.
+// They are only dereferenced through a borrow of the stack. Hence, a stack
+// can be sent and shared across threads whenever the elements (and the
+// allocator) can.
+unsafe impl<T: Send, A: Allocator + Send> Send for GenericStack<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for GenericStack<T, A> {}
+
+#[cfg(feature = "std")]
+impl<T, A> Stack<T> for GenericStack<T, A>
+where
+    T: Debug + PartialEq + Clone + Display,
+    A: Allocator + Clone + Default,
+{
+    /// Create a new monomorphic stack storing elements of type `<T>`.
+    /// # Example
+    ///
+    /// ```
+    /// // We need to import this trait to use the methods of this trait.
+    /// // We can import an implementation like `ll_stack`
+    /// use stack_trait::Stack;
+    /// use ll_stack::GenericStack;
+    /// // We create a stack of u128
+    /// let mut stack : GenericStack<u128> = GenericStack::new();
+    /// ```
+    fn new() -> Self {
+        GenericStack::new_in(A::default())
+    }
+
+    /// push a new element on the top element of the stack.
+    ///
+    /// # Arguments
+    ///  - `element` to be pushed on the stack
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // We need to import this trait to use the methods of this trait.
+    /// // We can import an implementation like `ll_stack`
+    /// use stack_trait::Stack;
+    /// use ll_stack::GenericStack;
+    /// // We create a stack of u64
+    /// let mut stack = GenericStack::new();
+    ///
+    /// // we an push an element to the stack
+    /// stack.push(1u64);
+    /// assert_eq!(stack.peek(), Some(&1u64));
+    /// ```
+    fn push(&mut self, element: T) {
+        trace!("push {element:?}: length {}", self.len + 1);
+        let node = self.allocate_node(element);
+        self.push_node(node);
+    }
+
+    /// Returns the top element of the stack if it exists, i.e.,
+    /// the last element that was pushed on the stack and not yet
+    /// removed by a preceding call to `pop`
+    ///
+    /// # Arguments
+    ///  - `pop` does not take any arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // We need to import this trait to use the methods of this trait.
+    /// // We can import an implementation like `ll_stack`
+    /// use stack_trait::Stack;
+    /// use ll_stack::GenericStack;
+    /// // We create a stack of i32
+    /// let mut stack = GenericStack::new();
+    ///
+    /// // Initially, the stack is empty:
+    /// assert_eq!(stack.pop(), None);
+    /// // we an push an element to the stack
+    /// stack.push(1);
+    /// assert_eq!(stack.pop(), Some(1));
+    /// ```
+    fn pop(&mut self) -> Option<T> {
+        let node = self.pop_node()?;
+        // SAFETY: the node was allocated by this stack and is unlinked
+        let element = unsafe { self.free_node(node) };
+        trace!("pop {element:?}: length {}", self.len);
+        Some(element)
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    /// This will return `None` if the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stack_trait::Stack;
+    /// use ll_stack::GenericStack;
+    /// // We create a stack of u128
+    /// let mut stack : GenericStack<u128> = GenericStack::new();
+    ///     println!("Top element: {:?}", stack.peek());
+    /// ```
+    fn peek(&self) -> Option<&T> {
+        GenericStack::peek(self)
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    /// This will return `None` if the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stack_trait::Stack;
+    /// use ll_stack::GenericStack;
+    /// // We create a stack of u128
+    /// let mut stack : GenericStack<u128> = GenericStack::new();
+    ///   stack.peek_mut().map(|value| { *value += 1; } );
+    /// ```
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        GenericStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, A> StackExt<T> for GenericStack<T, A>
+where
+    T: Debug + PartialEq + Clone + Display,
+    A: Allocator + Clone + Default,
+{
+    fn len(&self) -> usize {
+        GenericStack::len(self)
+    }
+}
+
+/// The capacity of a [`GenericStack`] includes the nodes of its node pool.
+#[cfg(feature = "std")]
+impl<T, A> CapacityStack<T> for GenericStack<T, A>
+where
+    T: Debug + PartialEq + Clone + Display,
+    A: Allocator + Clone + Default,
+{
+    fn with_capacity(capacity: usize) -> Self {
+        let mut stack = GenericStack::new_in(A::default());
+        stack.reserve(capacity);
+        stack
+    }
+
+    fn capacity(&self) -> usize {
+        GenericStack::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        GenericStack::reserve(self, additional);
+    }
+}
+
+///
+/// We define trait Iterators to define iterators for
+/// [`GenericStack`]:
+///
+///  - `iter`:
+///  - `iter_mut`:
+///
+/// The consuming iterator `into_iter` is provided by the standard trait
+/// [`IntoIterator`], which is implemented for `GenericStack<T>`,
+/// `&GenericStack<T>`, and `&mut GenericStack<T>`.
+pub trait Iterators<T: Debug + PartialEq + Clone + Display>:
+    Debug + Display + Clone + PartialEq
+{
+    /// iterator for `ll_stack<T>`
+    fn iter(&self) -> Iter<'_, T>;
+
+    /// mutable iterator for `ll_stack<T>`
+    fn iter_mut(&mut self) -> IterMut<'_, T>;
+}
+
+impl<T, A> Iterators<T> for GenericStack<T, A>
+where
+    T: Debug + PartialEq + Clone + Display,
+    A: Allocator + Clone,
+{
+    fn iter(&self) -> Iter<'_, T> {
+        GenericStack::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> IterMut<'_, T> {
+        GenericStack::iter_mut(self)
+    }
+}
+
+/// Consumes the stack and returns its elements from the top to the bottom.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack = GenericStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// let mut elements = Vec::new();
+/// for v in stack {
+///     elements.push(v);
+/// }
+/// assert_eq!(elements, vec![2, 1]);
+/// ```
+impl<T, A: Allocator> IntoIterator for GenericStack<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+/// Borrows the elements of the stack from the top to the bottom, i.e.,
+/// we can write `for v in &stack`.
+impl<'a, T, A: Allocator> IntoIterator for &'a GenericStack<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Mutably borrows the elements of the stack from the top to the bottom,
+/// i.e., we can write `for v in &mut stack`.
+impl<'a, T, A: Allocator> IntoIterator for &'a mut GenericStack<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Creates a stack from an iterator: the items are pushed in the order
+/// in which they are yielded, i.e., the first item ends up at the bottom
+/// and the last item ends up on the top of the stack.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack = (0..10).collect::<GenericStack<_>>();
+/// assert_eq!(stack.len(), 10);
+/// assert_eq!(stack.pop(), Some(9));
+/// ```
+impl<T> FromIterator<T> for GenericStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = GenericStack::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+/// Pushes all items of an iterator on the stack in the order in which
+/// they are yielded, i.e., the last item ends up on the top of the stack.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack = GenericStack::new();
+/// stack.push(1);
+/// stack.extend(vec![2, 3]);
+/// assert_eq!(stack.pop(), Some(3));
+/// assert_eq!(stack.len(), 2);
+/// ```
+impl<T, A: Allocator> Extend<T> for GenericStack<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_all(iter);
+    }
+}
+
+/// Pushes copies of all borrowed items of an iterator on the stack.
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for GenericStack<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Converts a `Vec<T>` into a stack: the elements are pushed from the
+/// first to the last element, i.e., the last element becomes the top.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let mut stack = GenericStack::from(vec![1, 2, 3]);
+/// assert_eq!(stack.pop(), Some(3));
+/// ```
+impl<T> From<Vec<T>> for GenericStack<T> {
+    fn from(elements: Vec<T>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+/// Converts a stack into a `Vec<T>`: the bottom of the stack becomes the
+/// first element and the top becomes the last element, i.e., this is
+/// the inverse of `From<Vec<T>> for GenericStack<T>`. Use
+/// [`GenericStack::into_vec`] for the opposite order.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::GenericStack;
+/// let stack = GenericStack::from(vec![1, 2, 3]);
+/// assert_eq!(Vec::from(stack), vec![1, 2, 3]);
+/// ```
+impl<T, A: Allocator> From<GenericStack<T, A>> for Vec<T> {
+    fn from(stack: GenericStack<T, A>) -> Self {
+        stack.into_iter().rev().collect()
+    }
+}
+
+/// Consuming iterator over the elements of a stack: `next` pops the
+/// elements from the top, `next_back` removes them from the bottom, i.e.,
+/// `into_iter().rev()` returns the elements in insertion order.
+pub struct IntoIter<T, A: Allocator = Global>(GenericStack<T, A>);
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        // access fields of a tuple struct numerically
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.0.pop_bottom_node()?;
+        // SAFETY: the node was allocated by the stack and is unlinked
+        Some(unsafe { self.0.free_node(node) })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+/// Once the stack is empty, `pop` keeps returning `None`.
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// Draining iterator returned by [`GenericStack::drain`].
+pub struct Drain<'a, T, A: Allocator = Global> {
+    stack: &'a mut GenericStack<T, A>,
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.stack.len, Some(self.stack.len))
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+/// Elements that were not yet yielded are dropped together with the [`Drain`].
+impl<T, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        self.stack.clear();
+    }
+}
+
+/// Iterator over borrowed elements of a stack. Since the nodes are linked
+/// in both directions, [`Iter`] can be consumed from the top (`next`) and
+/// from the bottom (`next_back`). We count the remaining elements to
+/// detect when both ends meet.
+pub struct Iter<'a, T> {
+    next: Link<T>,
+    back: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.next.map(|node| {
+            // SAFETY: the node is owned by the borrowed stack which outlives `'a`
+            let node = unsafe { &*node.as_ptr() };
+            self.len -= 1;
+            self.next = node.next;
+            &node.element
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| {
+            // SAFETY: the node is owned by the borrowed stack which outlives `'a`
+            let node = unsafe { &*node.as_ptr() };
+            self.len -= 1;
+            self.back = node.prev;
+            &node.element
+        })
+    }
+}
+
+/// Once the remaining length is zero, [`Iter`] keeps returning `None`.
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// The remaining length of an [`Iter`] is always known.
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+/// Cloning an [`Iter`] does not clone the elements: both iterators continue
+/// at the same position.
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            next: self.next,
+            back: self.back,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator over mutably borrowed elements of a stack. Like [`Iter`], it
+/// can be consumed from both ends, and the remaining length guarantees
+/// that no element is yielded twice.
+pub struct IterMut<'a, T> {
+    next: Link<T>,
+    back: Link<T>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.next.map(|node| {
+            // SAFETY: the node is owned by the mutably borrowed stack which
+            // outlives `'a`, and each node is yielded only once.
+            let node = unsafe { &mut *node.as_ptr() };
+            self.len -= 1;
+            self.next = node.next;
+            &mut node.element
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| {
+            // SAFETY: see `next`
+            let node = unsafe { &mut *node.as_ptr() };
+            self.len -= 1;
+            self.back = node.prev;
+            &mut node.element
+        })
+    }
+}
+
+/// Once the remaining length is zero, [`IterMut`] keeps returning `None`.
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// The remaining length of an [`IterMut`] is always known.
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+// SAFETY: the iterators hold raw links instead of references to the nodes,
+// but behave like `&'a T` and `&'a mut T`, respectively.
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // `GenericStack<T>` and its iterators must be as thread-safe as `T`
+    assert_impl_all!(GenericStack<i32>: Send, Sync);
+    assert_impl_all!(IntoIter<i32>: Send, Sync);
+    assert_impl_all!(Iter<'static, i32>: Send, Sync);
+    assert_impl_all!(IterMut<'static, i32>: Send, Sync);
+    assert_impl_all!(Drain<'static, i32>: Send, Sync);
+    assert_not_impl_any!(GenericStack<Rc<i32>>: Send, Sync);
+    assert_not_impl_any!(IntoIter<Rc<i32>>: Send, Sync);
+    assert_not_impl_any!(Iter<'static, Rc<i32>>: Send, Sync);
+    assert_not_impl_any!(IterMut<'static, Rc<i32>>: Send, Sync);
+    assert_not_impl_any!(Drain<'static, Rc<i32>>: Send, Sync);
+    // `Cell<T>` is `Send` but not `Sync`
+    assert_impl_all!(GenericStack<Cell<i32>>: Send);
+    assert_not_impl_any!(GenericStack<Cell<i32>>: Sync);
+    assert_not_impl_any!(Iter<'static, Cell<i32>>: Send, Sync);
+    assert_impl_all!(IterMut<'static, Cell<i32>>: Send);
+    assert_not_impl_any!(IterMut<'static, Cell<i32>>: Sync);
+
+    #[test]
+    fn basics() {
+        let mut stack = GenericStack::new();
+
+        // Check empty stack behaves right
+        assert_eq!(stack.pop(), None);
+
+        // Populate stack
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        // Check normal removal
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        stack.push(4);
+        stack.push(5);
+
+        // Check normal removal
+        assert_eq!(stack.pop(), Some(5));
+        assert_eq!(stack.pop(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn len() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.len(), 0);
+        assert!(stack.is_empty());
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.len(), 3);
+        assert!(!stack.is_empty());
+
+        stack.pop();
+        assert_eq!(stack.len(), 2);
+        stack.pop();
+        stack.pop();
+        assert_eq!(stack.len(), 0);
+        assert!(stack.is_empty());
+
+        // popping an empty stack must not change the length
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.len(), 0);
+    }
+
+    #[test]
+    fn clear() {
+        let mut stack = GenericStack::new();
+        stack.clear();
+        assert!(stack.is_empty());
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        stack.clear();
+        assert_eq!(stack.len(), 0);
+        assert_eq!(stack.pop(), None);
+
+        // the stack can be reused after clearing it
+        stack.push(4);
+        assert_eq!(stack.peek(), Some(&4));
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn max_depth() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.max_depth(), usize::MAX);
+        assert_eq!(stack.push_checked(1), Ok(()));
+
+        let mut stack = GenericStack::with_max_depth(2);
+        assert_eq!(stack.push_checked(1), Ok(()));
+        assert_eq!(stack.push_checked(2), Ok(()));
+        assert_eq!(stack.push_checked(3), Err(StackFullError(3)));
+        assert_eq!(stack.len(), 2);
+        // the clone has the same limit
+        let mut clone = stack.clone();
+        assert_eq!(clone.push_checked(3), Err(StackFullError(3)));
+
+        // `push` ignores the limit
+        stack.push(3);
+        assert_eq!(
+            stack.push_checked(4).map_err(StackFullError::into_inner),
+            Err(4)
+        );
+        stack.clear();
+        assert_eq!(stack.push_checked(4), Ok(()));
+
+        let mut stack = GenericStack::with_max_depth(0);
+        assert_eq!(stack.push_checked(1), Err(StackFullError(1)));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn drop_deep_stack() {
+        // Miri is too slow for a million nodes
+        let depth = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let mut stack = GenericStack::new();
+        for i in 0..depth {
+            stack.push(i);
+        }
+        // a recursive drop would overflow the call stack here
+        drop(stack);
+    }
+
+    #[test]
+    fn clone_deep_stack() {
+        let depth = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let stack: GenericStack<_> = (0..depth).collect();
+        // a recursive clone would overflow the call stack here
+        let clone = stack.clone();
+        assert_eq!(clone.len(), depth);
+        assert_eq!(clone.peek(), Some(&(depth - 1)));
+        assert_eq!(clone.iter().next_back(), Some(&0));
+        assert!(clone == stack);
+    }
+
+    /// counts the nodes that are currently allocated
+    #[derive(Clone, Default)]
+    struct Counting(Rc<Cell<usize>>);
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.set(self.0.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn allocator() {
+        let counting = Counting::default();
+        let mut stack = GenericStack::new_in(counting.clone());
+        stack.extend(1..=6);
+        assert_eq!(counting.0.get(), 6);
+
+        assert_eq!(stack.pop(), Some(6));
+        assert_eq!(stack.pop_n(2), vec![5, 4]);
+        // the nodes are kept in the pool
+        assert_eq!(counting.0.get(), 6);
+        stack.shrink_pool();
+        assert_eq!(counting.0.get(), 3);
+
+        let clone = stack.clone();
+        assert_eq!(counting.0.get(), 6);
+        assert_eq!(clone, stack);
+
+        stack.retain(|v| v % 2 == 1);
+        assert_eq!(stack.capacity(), 3);
+        stack.reverse();
+        assert_eq!(stack.iter().collect::<Vec<_>>(), vec![&1, &3]);
+
+        drop(clone);
+        assert_eq!(counting.0.get(), 3);
+        assert_eq!(stack.into_iter().next(), Some(1));
+        assert_eq!(counting.0.get(), 0);
+    }
+
+    #[test]
+    fn pool() {
+        let counting = Counting::default();
+        let mut stack = GenericStack::new_in(counting.clone());
+        stack.reserve(3);
+        assert_eq!(counting.0.get(), 3);
+        assert_eq!(stack.capacity(), 3);
+
+        // pushing and popping reuses the nodes of the pool
+        for round in 0..10 {
+            stack.extend([round, round + 1, round + 2]);
+            assert_eq!(stack.pop_n(3), vec![round + 2, round + 1, round]);
+        }
+        assert_eq!(counting.0.get(), 3);
+
+        stack.extend(0..5);
+        assert_eq!(counting.0.get(), 5);
+        stack.clear();
+        assert_eq!(stack.capacity(), 5);
+        stack.reserve(2);
+        assert_eq!(counting.0.get(), 5);
+        stack.shrink_pool();
+        assert_eq!(stack.capacity(), 0);
+        assert_eq!(counting.0.get(), 0);
+
+        // elements of pooled nodes are dropped exactly once
+        let rc = Rc::new(());
+        let mut stack = GenericStack::new_in(counting.clone());
+        stack.push(Rc::clone(&rc));
+        stack.pop();
+        assert_eq!(Rc::strong_count(&rc), 1);
+        stack.push(Rc::clone(&rc));
+        drop(stack);
+        assert_eq!(Rc::strong_count(&rc), 1);
+        assert_eq!(counting.0.get(), 0);
+    }
+
+    #[test]
+    fn relaxed_bounds() {
+        // `Vec<u8>` does not implement `Display`
+        let mut stack: GenericStack<Vec<u8>> = GenericStack::new();
+        stack.push(vec![1]);
+        stack.push(vec![2, 3]);
+        assert_eq!(stack.peek(), Some(&vec![2, 3]));
+        if let Some(v) = stack.peek_mut() {
+            v.push(4);
+        }
+        assert_eq!(stack.iter().map(Vec::len).sum::<usize>(), 4);
+        for v in stack.iter_mut() {
+            v.clear();
+        }
+        assert_eq!(stack.pop(), Some(vec![]));
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn covariance() {
+        // `GenericStack<T>` and its iterators are covariant in `T`, like a `Vec<T>`
+        fn shorten<'a>(stack: GenericStack<&'static str>) -> GenericStack<&'a str> {
+            stack
+        }
+        fn shorten_iter<'a, 'b>(iter: Iter<'a, &'static str>) -> Iter<'a, &'b str> {
+            iter
+        }
+        fn shorten_into_iter<'a>(iter: IntoIter<&'static str>) -> IntoIter<&'a str> {
+            iter
+        }
+
+        let local = String::from("b");
+        let mut stack = shorten(GenericStack::from(vec!["a"]));
+        stack.push(&local);
+        assert_eq!(stack.len(), 2);
+        let static_stack = GenericStack::from(vec!["c"]);
+        assert_eq!(shorten_iter(static_stack.iter()).next(), Some(&"c"));
+        assert_eq!(
+            shorten_into_iter(static_stack.into_iter()).next(),
+            Some("c")
+        );
+    }
+
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Machine {
+            operands: GenericStack<i64>,
+        }
+
+        let mut machine = Machine::default();
+        assert!(machine.operands.is_empty());
+        machine.operands.push(7);
+
+        let operands = std::mem::take(&mut machine.operands);
+        assert!(machine.operands.is_empty());
+        assert_eq!(operands.peek(), Some(&7));
+    }
+
+    #[test]
+    fn debug() {
+        let stack = GenericStack::from(vec!["a", "b", "c"]);
+        assert_eq!(
+            format!("{stack:?}"),
+            "GenericStack { head: Some(Node { element: \"c\", next: Some(Node { element: \"b\", \
+             next: Some(Node { element: \"a\", next: None }) }) }), len: 3 }"
+        );
+        assert_eq!(
+            format!("{stack:#?}"),
+            "GenericStack (len: 3) [\n    0: \"c\" <- top\n    1: \"b\"\n    2: \"a\"\n]"
+        );
+        let empty: GenericStack<u8> = GenericStack::new();
+        assert_eq!(format!("{empty:#?}"), "GenericStack (len: 0) [\n]");
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(stack: &GenericStack<i32>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            stack.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let a: GenericStack<_> = (1..=3).collect();
+        let b: GenericStack<_> = (1..=3).collect();
+        let c: GenericStack<_> = (1..=4).collect();
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(hash_of(&a), hash_of(&c));
+    }
+
+    #[test]
+    fn eq_compares_lengths_first() {
+        /// an element that must never be compared
+        #[derive(Debug)]
+        struct Incomparable;
+        impl PartialEq for Incomparable {
+            fn eq(&self, _: &Self) -> bool {
+                panic!("the elements were compared")
+            }
+        }
+
+        let a: GenericStack<_> = (0..3).map(|_| Incomparable).collect();
+        let b: GenericStack<_> = (0..4).map(|_| Incomparable).collect();
+        assert!(a != b);
+
+        // a recursive comparison would overflow the call stack here
+        let depth = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let a: GenericStack<_> = (0..depth).collect();
+        let mut b = a.clone();
+        assert!(a == b);
+        if let Some(bottom) = b.iter_mut().next_back() {
+            *bottom = depth;
+        }
+        assert!(a != b);
+    }
+
+    #[test]
+    fn eq() {
+        use std::collections::HashSet;
+
+        fn assert_eq_impl<E: Eq>() {}
+        assert_eq_impl::<GenericStack<i32>>();
+
+        let a: GenericStack<_> = (1..=3).collect();
+        let b: GenericStack<_> = (1..=3).collect();
+        let c: GenericStack<_> = (1..=4).collect();
+
+        let mut set = HashSet::new();
+        set.insert(a.clone());
+        set.insert(b);
+        set.insert(c);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+    }
+
+    #[test]
+    fn ord() {
+        use std::collections::BTreeMap;
+
+        let empty: GenericStack<i32> = GenericStack::new();
+        let a: GenericStack<_> = vec![5, 1].into_iter().collect();
+        let b: GenericStack<_> = vec![1, 2].into_iter().collect();
+        let c: GenericStack<_> = vec![0, 1, 2].into_iter().collect();
+
+        // compared from the top: 1 < 2, and a prefix is smaller
+        assert!(a < b);
+        assert!(b < c);
+        assert!(empty < a);
+        assert_eq!(b.cmp(&b.clone()), Ordering::Equal);
+
+        let mut stacks = vec![c.clone(), a.clone(), empty.clone(), b.clone()];
+        stacks.sort();
+        assert_eq!(stacks, vec![empty, a.clone(), b, c]);
+
+        let mut map = BTreeMap::new();
+        map.insert(a.clone(), "a");
+        assert_eq!(map.get(&a), Some(&"a"));
+
+        let nan: GenericStack<f64> = vec![f64::NAN].into_iter().collect();
+        assert_eq!(nan.partial_cmp(&nan), None);
+    }
+
+    #[test]
+    fn pop_n() {
+        let mut stack: GenericStack<_> = (1..=5).collect();
+        assert_eq!(stack.pop_n(0), Vec::<i32>::new());
+        assert_eq!(stack.len(), 5);
 
-        // Push some more just to make sure nothing's corrupted
-        stack.push(4);
-        stack.push(5);
+        assert_eq!(stack.pop_n(2), vec![5, 4]);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.peek(), Some(&3));
+        assert_eq!(
+            stack.iter().rev().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
 
-        // Check normal removal
-        assert_eq!(stack.pop(), Some(5));
-        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop_n(3), vec![3, 2, 1]);
+        assert!(stack.is_empty());
+        assert_eq!(stack.iter().next_back(), None);
+        assert_eq!(stack.pop_n(1), Vec::<i32>::new());
 
-        // Check exhaustion
-        assert_eq!(stack.pop(), Some(1));
-        assert_eq!(stack.pop(), None);
+        stack.extend(1..=2);
+        assert_eq!(stack.pop_n(usize::MAX), vec![2, 1]);
+    }
+
+    #[test]
+    fn pop_if() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.pop_if(|_| true), None::<i32>);
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(stack.pop_if(|&top| top % 2 == 1), None);
+        assert_eq!(stack.pop_if(|&top| top % 2 == 0), Some(2));
+        assert_eq!(stack.pop_if(|&top| top == 1), Some(1));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn replace_top() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.replace_top(String::from("a")), None);
+        stack.push(String::from("b"));
+        let capacity = stack.capacity();
+        assert_eq!(stack.replace_top(String::from("c")).as_deref(), Some("b"));
+        assert_eq!(stack.capacity(), capacity);
+        assert_eq!(stack.to_vec(), vec!["c", "a"]);
+    }
+
+    #[test]
+    fn take_top() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.take_top(), None::<String>);
+        stack.push(String::from("a"));
+        stack.push(String::from("b"));
+        assert_eq!(stack.take_top().as_deref(), Some("b"));
+        assert_eq!(stack.take_top().as_deref(), Some(""));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.to_vec(), vec!["", "a"]);
+    }
+
+    #[test]
+    fn push_all() {
+        let mut stack = GenericStack::new();
+        stack.push_all(Vec::new());
+        assert!(stack.is_empty());
+
+        stack.push(0);
+        stack.push_all(1..=3);
+        assert_eq!(stack.len(), 4);
+        assert_eq!(stack.peek(), Some(&3));
+        assert_eq!(stack.pop_n(4), vec![3, 2, 1, 0]);
     }
 
     #[test]
@@ -302,6 +2810,72 @@ mod test {
         assert_eq!(stack.pop(), Some(42));
     }
 
+    #[test]
+    fn peek_or_push() {
+        let mut stack = GenericStack::new();
+        *stack.peek_or_push(1) += 10;
+        assert_eq!(stack.peek_or_push(2), &mut 11);
+        assert_eq!(stack.len(), 1);
+
+        let mut stack = GenericStack::new();
+        let mut calls = 0;
+        for _ in 0..3 {
+            stack.peek_or_push_with(|| {
+                calls += 1;
+                vec![0]
+            });
+        }
+        assert_eq!(calls, 1);
+        stack.peek_or_push_with(Vec::new).push(1);
+        assert_eq!(stack.pop(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn get() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.get(0), None);
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.get(0), stack.peek());
+        assert_eq!(stack.get(1), Some(&2));
+        assert_eq!(stack.get(2), Some(&1));
+        assert_eq!(stack.get(3), None);
+        assert_eq!(stack.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.get_mut(0), None);
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.get_mut(0), Some(&mut 3));
+        assert_eq!(stack.get_mut(3), None);
+
+        if let Some(value) = stack.get_mut(1) {
+            *value = 42;
+        }
+        assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![3, 42, 1]);
+    }
+
+    #[test]
+    fn index() {
+        let mut stack: GenericStack<_> = (1..=3).collect();
+        assert_eq!((stack[0], stack[1], stack[2]), (3, 2, 1));
+        stack[0] += 10;
+        stack[2] *= 5;
+        assert_eq!(stack.to_vec(), vec![13, 2, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the len is 2 but the index is 2")]
+    fn index_out_of_bounds() {
+        let stack = GenericStack::from(vec![1, 2]);
+        let _ = stack[2];
+    }
+
     #[test]
     fn into_iter() {
         let mut stack = GenericStack::new();
@@ -316,6 +2890,165 @@ mod test {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn push_bottom() {
+        let mut stack = GenericStack::new();
+        stack.push_bottom(2);
+        assert_eq!(stack.peek(), Some(&2));
+        stack.push(3);
+        stack.push_bottom(1);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.peek_bottom(), Some(&1));
+        assert!(stack.iter().eq([3, 2, 1].iter()));
+        assert!(stack.iter().rev().eq([1, 2, 3].iter()));
+        assert_eq!(stack.pop_n(3), vec![3, 2, 1]);
+        assert_eq!(stack.peek_bottom(), None);
+    }
+
+    #[test]
+    fn peek_bottom() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.peek_bottom(), None);
+        assert_eq!(stack.peek_bottom_mut(), None);
+        stack.push(1);
+        assert_eq!(stack.peek_bottom(), Some(&1));
+        stack.push(2);
+        if let Some(bottom) = stack.peek_bottom_mut() {
+            *bottom = 10;
+        }
+        assert_eq!(stack.peek_bottom(), Some(&10));
+        assert_eq!(stack.peek(), Some(&2));
+        stack.pop();
+        stack.pop();
+        assert_eq!(stack.peek_bottom(), None);
+    }
+
+    #[test]
+    fn iter_with_depth() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        assert_eq!(
+            stack.iter_with_depth().collect::<Vec<_>>(),
+            vec![(0, &3), (1, &2), (2, &1)]
+        );
+        assert_eq!(
+            stack.iter_with_depth().rev().collect::<Vec<_>>(),
+            vec![(2, &1), (1, &2), (0, &3)]
+        );
+        assert_eq!(GenericStack::<u8>::new().iter_with_depth().next(), None);
+    }
+
+    #[test]
+    fn zip() {
+        let a: GenericStack<_> = (1..=4).collect();
+        let b = GenericStack::from(vec!['x', 'y']);
+        assert_eq!(a.zip(&b).len(), 2);
+        assert_eq!(a.zip(&b).collect::<Vec<_>>(), vec![(&4, &'y'), (&3, &'x')]);
+        assert_eq!(
+            a.zip(&b).rev().collect::<Vec<_>>(),
+            vec![(&3, &'x'), (&4, &'y')]
+        );
+        assert_eq!(b.zip(&a).next(), Some((&'y', &4)));
+        assert_eq!(a.zip(&GenericStack::<u8>::new()).next(), None);
+    }
+
+    #[test]
+    fn into_iter_rev() {
+        let stack: GenericStack<_> = (1..=4).collect();
+        let mut iter = stack.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(1));
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next_back(), Some(2));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        // the remaining elements are dropped with the iterator
+        let stack: GenericStack<_> = (1..=3).map(|v| v.to_string()).collect();
+        let mut iter = stack.into_iter();
+        assert_eq!(iter.next_back().as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn iter_mut_rev() {
+        let mut stack: GenericStack<_> = (1..=3).collect();
+        let mut iter = stack.iter_mut();
+        assert_eq!(iter.len(), 3);
+        *iter.next_back().unwrap() = 10;
+        *iter.next().unwrap() = 30;
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some(&mut 2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(Vec::from(stack), vec![10, 2, 30]);
+    }
+
+    #[test]
+    fn into_iterator() {
+        let mut stack = GenericStack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        let mut sum = 0;
+        for v in &stack {
+            sum += *v;
+        }
+        assert_eq!(sum, 6);
+
+        for v in &mut stack {
+            *v *= 10;
+        }
+        assert_eq!(stack.peek(), Some(&30));
+
+        let mut elements = Vec::new();
+        for v in stack {
+            elements.push(v);
+        }
+        assert_eq!(elements, vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn from_iter() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        assert_eq!(stack.len(), 3);
+
+        // the first item is at the bottom, the last item on the top
+        let mut iter = stack.into_iter();
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+
+        let empty: GenericStack<i32> = std::iter::empty().collect();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn extend() {
+        let mut stack = GenericStack::new();
+        stack.push(1);
+        stack.extend(vec![2, 3]);
+        stack.extend(&[4, 5]);
+        assert_eq!(stack.len(), 5);
+        assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn from_vec() {
+        let mut stack = GenericStack::from(vec![1, 2, 3]);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.peek(), Some(&3));
+
+        stack.push(4);
+        let elements: Vec<_> = stack.into();
+        assert_eq!(elements, vec![1, 2, 3, 4]);
+
+        let empty = GenericStack::<u8>::from(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(Vec::from(empty), Vec::<u8>::new());
+    }
+
     #[test]
     fn iter() {
         let mut stack = GenericStack::new();
@@ -329,6 +3062,62 @@ mod test {
         assert_eq!(iter.next(), Some(&1));
     }
 
+    #[test]
+    fn iter_exact_size() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        let mut iter = stack.iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        let clone = iter.clone();
+        iter.next_back();
+        assert_eq!(iter.len(), 1);
+        assert_eq!(clone.len(), 2);
+        assert_eq!(clone.collect::<Vec<_>>(), vec![&2, &1]);
+    }
+
+    #[test]
+    fn iter_rev() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.iter().next_back(), None);
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        let mut iter = stack.iter().rev();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+
+        // both ends meet in the middle
+        let mut iter = stack.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next_back(), Some(&1));
+        assert_eq!(iter.next_back(), Some(&2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        // the back links are maintained by pop
+        assert_eq!(stack.pop(), Some(3));
+        stack.push(4);
+        assert_eq!(stack.iter().rev().collect::<Vec<_>>(), vec![&1, &2, &4]);
+        stack.pop();
+        stack.pop();
+        assert_eq!(stack.iter().rev().collect::<Vec<_>>(), vec![&1]);
+        stack.pop();
+        assert_eq!(stack.iter().next_back(), None);
+
+        // the back links are maintained by clear and clone
+        stack.push(5);
+        stack.clear();
+        assert_eq!(stack.iter().next_back(), None);
+        stack.push(6);
+        stack.push(7);
+        let copy = stack.clone();
+        drop(stack);
+        assert_eq!(copy.iter().rev().collect::<Vec<_>>(), vec![&6, &7]);
+    }
+
     #[test]
     fn iter_mut() {
         let mut stack = GenericStack::new();
@@ -342,6 +3131,383 @@ mod test {
         assert_eq!(iter.next(), Some(&mut 1));
     }
 
+    #[test]
+    fn from_slice() {
+        let elements = [String::from("x"), String::from("y")];
+        let stack = GenericStack::from_slice(&elements);
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.peek(), Some(&elements[1]));
+        assert_eq!(Vec::from(stack), elements);
+        assert!(GenericStack::<u8>::from_slice(&[]).is_empty());
+    }
+
+    #[test]
+    fn into_vec() {
+        let stack: GenericStack<_> = (1..=3).map(|v| v.to_string()).collect();
+        assert_eq!(stack.to_vec(), vec!["3", "2", "1"]);
+        let mut reversed = stack.to_vec();
+        reversed.reverse();
+        assert_eq!(Vec::from(stack.clone()), reversed);
+        assert_eq!(stack.into_vec(), vec!["3", "2", "1"]);
+        assert!(GenericStack::<u8>::new().into_vec().is_empty());
+    }
+
+    #[test]
+    fn drain() {
+        let mut stack: GenericStack<_> = (1..=3).collect();
+        assert_eq!(stack.drain().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert!(stack.is_empty());
+
+        // a partially consumed drain still empties the stack
+        stack.extend(vec![4, 5, 6]);
+        {
+            let mut drain = stack.drain();
+            assert_eq!(drain.next(), Some(6));
+        }
+        assert!(stack.is_empty());
+        assert_eq!(stack.iter().next_back(), None);
+
+        // the stack can be used again
+        stack.push(7);
+        assert_eq!(stack.peek(), Some(&7));
+    }
+
+    #[test]
+    fn append() {
+        let mut stack = GenericStack::from(vec![1, 2]);
+        let mut other = GenericStack::from(vec![3, 4, 5]);
+        stack.append(&mut other);
+        assert_eq!(stack.len(), 5);
+        assert!(other.is_empty());
+        assert_eq!(other.iter().next_back(), None);
+        assert_eq!(
+            stack.iter().rev().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(stack.peek(), Some(&5));
+
+        // appending an empty stack does not change anything
+        stack.append(&mut other);
+        assert_eq!(stack.len(), 5);
+
+        // appending to an empty stack moves all elements
+        other.append(&mut stack);
+        assert!(stack.is_empty());
+        assert_eq!(Vec::from(other.clone()), vec![1, 2, 3, 4, 5]);
+
+        // both stacks remain usable
+        stack.push(6);
+        other.push(7);
+        assert_eq!(other.pop(), Some(7));
+        assert_eq!(other.iter().next_back(), Some(&1));
+        assert_eq!(stack.iter().next_back(), Some(&6));
+    }
+
+    #[test]
+    fn reverse() {
+        let mut stack: GenericStack<i32> = GenericStack::new();
+        stack.reverse();
+        assert!(stack.is_empty());
+
+        stack.extend(1..=4);
+        stack.reverse();
+        assert_eq!(stack.len(), 4);
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            stack.iter().rev().copied().collect::<Vec<_>>(),
+            vec![4, 3, 2, 1]
+        );
+
+        stack.reverse();
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.iter().next_back(), Some(&1));
+    }
+
+    #[test]
+    fn swap_top() {
+        let mut stack = GenericStack::new();
+        assert!(!stack.swap_top());
+        stack.push(1);
+        assert!(!stack.swap_top());
+        assert_eq!(stack.peek(), Some(&1));
+
+        stack.push(2);
+        assert!(stack.swap_top());
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(stack.iter().rev().copied().collect::<Vec<_>>(), vec![2, 1]);
+
+        stack.push(3);
+        assert!(stack.swap_top());
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.into_iter().collect::<Vec<_>>(), vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn dup() {
+        let mut stack = GenericStack::new();
+        assert!(!stack.dup());
+        assert!(stack.is_empty());
+
+        stack.push(String::from("a"));
+        stack.push(String::from("b"));
+        assert!(stack.dup());
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.pop(), Some(String::from("b")));
+        assert_eq!(stack.pop(), Some(String::from("b")));
+        assert_eq!(stack.pop(), Some(String::from("a")));
+    }
+
+    #[test]
+    fn rot() {
+        let mut stack = GenericStack::from(vec![1, 2]);
+        assert!(!stack.rot());
+        assert_eq!(Vec::from(stack.clone()), vec![1, 2]);
+
+        stack.push(3);
+        assert!(stack.rot());
+        assert_eq!(Vec::from(stack.clone()), vec![2, 3, 1]);
+
+        // three rotations restore the original order
+        assert!(stack.rot());
+        assert!(stack.rot());
+        assert_eq!(Vec::from(stack.clone()), vec![1, 2, 3]);
+
+        // elements below the top three are not touched
+        let mut stack = GenericStack::from(vec![0, 1, 2, 3]);
+        assert!(stack.rot());
+        assert_eq!(stack.len(), 4);
+        assert_eq!(
+            stack.iter().rev().copied().collect::<Vec<_>>(),
+            vec![0, 2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn contains() {
+        let mut stack = GenericStack::new();
+        assert!(!stack.contains(&1));
+        stack.push(1);
+        stack.push(2);
+        assert!(stack.contains(&1));
+        assert!(stack.contains(&2));
+        assert!(!stack.contains(&3));
+        stack.pop();
+        assert!(!stack.contains(&2));
+    }
+
+    #[test]
+    fn position() {
+        let stack: GenericStack<_> = (1..=5).collect();
+        assert_eq!(stack.position(|v| *v == 5), Some(0));
+        assert_eq!(stack.position(|v| *v < 3), Some(3));
+        assert_eq!(stack.position(|v| *v > 5), None);
+        let depth = stack.position(|v| *v == 2).unwrap();
+        assert_eq!(stack.get(depth), Some(&2));
+        assert_eq!(stack.find(|v| *v < 3), Some(&2));
+        assert_eq!(stack.find(|v| *v > 5), None);
+        assert_eq!(GenericStack::<u8>::new().position(|_| true), None);
+    }
+
+    #[test]
+    fn retain() {
+        let mut stack: GenericStack<_> = (1..=10).collect();
+        let mut visited = Vec::new();
+        stack.retain(|v| {
+            visited.push(*v);
+            v % 3 != 0
+        });
+        assert_eq!(visited, vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(stack.len(), 7);
+        assert_eq!(
+            stack.iter().copied().collect::<Vec<_>>(),
+            vec![10, 8, 7, 5, 4, 2, 1]
+        );
+        assert_eq!(
+            stack.iter().rev().copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 7, 8, 10]
+        );
+
+        stack.retain(|_| false);
+        assert!(stack.is_empty());
+        assert_eq!(stack.iter().next_back(), None);
+    }
+
+    #[test]
+    fn map() {
+        let stack: GenericStack<_> = (1..=3).collect();
+        let mut visited = Vec::new();
+        let mapped = stack.map(|v| {
+            visited.push(v);
+            v * 10
+        });
+        assert_eq!(visited, vec![3, 2, 1]);
+        assert_eq!(mapped.len(), 3);
+        assert_eq!(mapped.peek(), Some(&30));
+        assert_eq!(Vec::from(mapped), vec![10, 20, 30]);
+        assert!(GenericStack::<u8>::new().map(|v| v as char).is_empty());
+    }
+
+    #[test]
+    fn filter() {
+        let stack: GenericStack<_> = (1..=6).map(|v| v.to_string()).collect();
+        let mut visited = Vec::new();
+        let filtered = stack.filtered(|v| {
+            visited.push(v.clone());
+            v != "2" && v != "5"
+        });
+        assert_eq!(visited, vec!["6", "5", "4", "3", "2", "1"]);
+        assert_eq!(filtered.peek().map(String::as_str), Some("6"));
+        assert_eq!(Vec::from(filtered), vec!["1", "3", "4", "6"]);
+
+        let filtered = stack.filter(|v| v.as_str() > "3");
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(Vec::from(filtered), vec!["4", "5", "6"]);
+    }
+
+    #[test]
+    fn partition() {
+        let stack: GenericStack<_> = (1..=7).map(|v| v.to_string()).collect();
+        let mut visited = Vec::new();
+        let (small, large) = stack.partition(|v| {
+            visited.push(v.clone());
+            v.as_str() < "4"
+        });
+        assert_eq!(visited, vec!["7", "6", "5", "4", "3", "2", "1"]);
+        assert_eq!(small.len(), 3);
+        assert_eq!(large.len(), 4);
+        assert!(large.iter().rev().eq(["4", "5", "6", "7"].iter()));
+        assert_eq!(large.peek_bottom().map(String::as_str), Some("4"));
+        assert_eq!(Vec::from(small), vec!["1", "2", "3"]);
+
+        let (all, none) = GenericStack::from(vec![1, 2]).partition(|_| true);
+        assert_eq!(all.len(), 2);
+        assert!(none.is_empty());
+        let (none, all) = GenericStack::from(vec![1, 2]).partition(|_| false);
+        assert!(none.is_empty());
+        assert_eq!(none.peek_bottom(), None);
+        assert_eq!(Vec::from(all), vec![1, 2]);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut stack: GenericStack<i32> = GenericStack::new();
+        stack.dedup();
+        assert!(stack.is_empty());
+
+        let mut stack = GenericStack::from(vec![1, 1, 1]);
+        stack.dedup();
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.peek(), Some(&1));
+
+        let mut stack = GenericStack::from(vec![2, 2, 3, 2, 4, 4]);
+        stack.dedup();
+        assert_eq!(stack.len(), 4);
+        assert!(stack.iter().eq([4, 2, 3, 2].iter()));
+        assert!(stack.iter().rev().eq([2, 3, 2, 4].iter()));
+
+        // like `Vec::dedup`, the lowest element of a run is kept
+        #[derive(Debug)]
+        struct CaseInsensitive(&'static str);
+        impl PartialEq for CaseInsensitive {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.eq_ignore_ascii_case(other.0)
+            }
+        }
+        let mut stack: GenericStack<_> = ["a", "A", "b", "B"]
+            .into_iter()
+            .map(CaseInsensitive)
+            .collect();
+        stack.dedup();
+        assert_eq!(
+            stack.iter().map(|s| s.0).collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+    }
+
+    #[test]
+    fn insert() {
+        let mut stack = GenericStack::new();
+        stack.insert(0, 2);
+        stack.insert(0, 4);
+        stack.insert(2, 1);
+        stack.insert(1, 3);
+        stack.insert(4, 0);
+        assert_eq!(stack.len(), 5);
+        assert!(stack.iter().eq([4, 3, 2, 1, 0].iter()));
+        assert!(stack.iter().rev().eq([0, 1, 2, 3, 4].iter()));
+        assert_eq!(stack.peek_bottom(), Some(&0));
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion depth 2 is greater than the length 1")]
+    fn insert_too_deep() {
+        GenericStack::from(vec![1]).insert(2, 0);
+    }
+
+    #[test]
+    fn remove() {
+        let mut stack: GenericStack<_> = (0..6).map(|v| v.to_string()).collect();
+        assert_eq!(stack.remove(6), None);
+        assert_eq!(stack.remove(4).as_deref(), Some("1"));
+        assert_eq!(stack.remove(1).as_deref(), Some("4"));
+        assert_eq!(stack.remove(3).as_deref(), Some("0"));
+        assert_eq!(stack.remove(0).as_deref(), Some("5"));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.peek().map(String::as_str), Some("3"));
+        assert_eq!(stack.peek_bottom().map(String::as_str), Some("2"));
+        // `insert` and `remove` are inverse operations
+        stack.insert(1, String::from("x"));
+        assert_eq!(stack.remove(1).as_deref(), Some("x"));
+        assert_eq!(Vec::from(stack), vec!["2", "3"]);
+    }
+
+    #[test]
+    fn rotate() {
+        let mut stack: GenericStack<_> = (1..=5).collect();
+        stack.rotate_up(0);
+        stack.rotate_up(5);
+        assert!(stack.iter().eq([5, 4, 3, 2, 1].iter()));
+
+        for n in 1..5 {
+            let mut rotated = stack.clone();
+            rotated.rotate_up(n);
+            let mut expected = Vec::from(stack.clone());
+            expected.rotate_right(n);
+            assert_eq!(Vec::from(rotated.clone()), expected);
+            assert!(rotated.iter().rev().eq(expected.iter()));
+            assert_eq!(rotated.len(), 5);
+            // rotating down undoes the rotation
+            rotated.rotate_down(n);
+            assert_eq!(rotated, stack);
+        }
+
+        let mut single = GenericStack::from(vec![1]);
+        single.rotate_up(1);
+        single.rotate_down(0);
+        assert_eq!(single.peek(), Some(&1));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot rotate 3 elements")]
+    fn rotate_too_many() {
+        GenericStack::from(vec![1, 2]).rotate_down(3);
+    }
+
+    #[test]
+    fn fused() {
+        fn assert_fused<I: FusedIterator>(mut iter: I) {
+            while iter.next().is_some() {}
+            assert!(iter.next().is_none());
+            assert!(iter.next().is_none());
+        }
+
+        let mut stack: GenericStack<_> = (1..=3).collect();
+        assert_fused(stack.iter());
+        assert_fused(stack.iter().rev());
+        assert_fused(stack.iter_mut());
+        assert_fused(stack.into_iter());
+    }
+
     #[test]
     fn examples() {
         use core::fmt::Debug;
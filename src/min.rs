@@ -0,0 +1,254 @@
+//! A stack which keeps track of its minimal element.
+
+use crate::display::fmt_head_list;
+use crate::GenericStack;
+#[cfg(feature = "std")]
+use crate::{Stack, StackExt};
+use alloc::rc::Rc;
+use core::fmt::{self, Debug, Display};
+
+/// `MinStack<T>` is a stack that returns its minimal element in O(1).
+///
+/// Each node stores, next to its element, the minimum of all elements
+/// below it. The minimum of the stack is the smaller one of the top
+/// element and the minimum stored in the top node. Since the stored
+/// minimum does not depend on the top element, the top element can still
+/// be modified with [`MinStack::peek_mut`].
+///
+/// To avoid cloning the elements, they are stored in an [`Rc`]: a node
+/// shares the minimum with the node in which it is stored. The top element
+/// is never shared, i.e., we can pop it by value and borrow it mutably.
+/// Since [`Rc`] is not thread-safe, a `MinStack` cannot be sent to
+/// another thread.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::MinStack;
+/// let mut stack = MinStack::new();
+/// stack.push(3);
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.min(), Some(&1));
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!(stack.min(), Some(&3));
+/// ```
+pub struct MinStack<T: Ord> {
+    stack: GenericStack<Entry<T>>,
+}
+
+struct Entry<T> {
+    element: Rc<T>,
+    /// the minimal element below this entry, `None` for the bottom entry
+    min_below: Option<Rc<T>>,
+}
+
+impl<T: Ord> Entry<T> {
+    /// the minimum of this entry and all entries below
+    fn min(&self) -> &Rc<T> {
+        match &self.min_below {
+            Some(min) if **min < *self.element => min,
+            _ => &self.element,
+        }
+    }
+}
+
+impl<T: Ord> MinStack<T> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        MinStack {
+            stack: GenericStack::new(),
+        }
+    }
+
+    /// push a new element on the top element of the stack.
+    pub fn push(&mut self, element: T) {
+        let min_below = self.stack.peek().map(|top| Rc::clone(top.min()));
+        self.stack.push(Entry {
+            element: Rc::new(element),
+            min_below,
+        });
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop().map(|entry| {
+            let Entry { element, min_below } = entry;
+            drop(min_below);
+            Rc::try_unwrap(element)
+                .ok()
+                .expect("the top element is never shared")
+        })
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.stack.peek().map(|top| &*top.element)
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack
+    /// is not empty. Modifying the top element updates the minimum.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.stack
+            .peek_mut()
+            .map(|top| Rc::get_mut(&mut top.element).expect("the top element is never shared"))
+    }
+
+    /// Returns the minimal element of the stack in O(1) or `None` if the
+    /// stack is empty.
+    #[must_use]
+    pub fn min(&self) -> Option<&T> {
+        self.stack.peek().map(|top| &**top.min())
+    }
+
+    /// iterator from the top to the bottom of the stack
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.stack.iter().map(|entry| &*entry.element)
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+impl<T: Ord> Default for MinStack<T> {
+    fn default() -> Self {
+        MinStack::new()
+    }
+}
+
+/// Cloning pushes clones of the elements from the bottom to the top on a
+/// new stack, i.e., the clone does not share any elements with `self`.
+impl<T: Ord + Clone> Clone for MinStack<T> {
+    fn clone(&self) -> Self {
+        let mut stack = MinStack::new();
+        for element in self.iter().rev() {
+            stack.push(element.clone());
+        }
+        stack
+    }
+}
+
+impl<T: Ord + Debug> Debug for MinStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> PartialEq for MinStack<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Ord> Eq for MinStack<T> {}
+
+/// [`MinStack`] prints its elements in the same format as
+/// [`crate::GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Ord + Display> Display for MinStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self.iter())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Debug + Clone + Display> Stack<T> for MinStack<T> {
+    fn new() -> Self {
+        MinStack::new()
+    }
+
+    fn push(&mut self, element: T) {
+        MinStack::push(self, element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        MinStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        MinStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        MinStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Ord + Debug + Clone + Display> StackExt<T> for MinStack<T> {
+    fn len(&self) -> usize {
+        MinStack::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn min() {
+        let mut stack = MinStack::new();
+        assert_eq!(stack.min(), None);
+
+        stack.push(5);
+        assert_eq!(stack.min(), Some(&5));
+        stack.push(7);
+        assert_eq!(stack.min(), Some(&5));
+        stack.push(2);
+        assert_eq!(stack.min(), Some(&2));
+        stack.push(2);
+        stack.push(9);
+        assert_eq!(stack.min(), Some(&2));
+
+        assert_eq!(stack.pop(), Some(9));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.min(), Some(&2));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.min(), Some(&5));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.to_string(), "head->7->5.");
+    }
+
+    #[test]
+    fn peek_mut_updates_min() {
+        let mut stack = MinStack::new();
+        stack.push(String::from("b"));
+        stack.push(String::from("c"));
+        assert_eq!(stack.min().map(String::as_str), Some("b"));
+
+        if let Some(top) = stack.peek_mut() {
+            *top = String::from("a");
+        }
+        assert_eq!(stack.min().map(String::as_str), Some("a"));
+        assert_eq!(stack.pop(), Some(String::from("a")));
+        assert_eq!(stack.min().map(String::as_str), Some("b"));
+    }
+
+    #[test]
+    fn clone() {
+        let mut stack = MinStack::new();
+        for v in [4, 1, 3] {
+            stack.push(v);
+        }
+        let mut copy = stack.clone();
+        assert_eq!(copy, stack);
+        assert_eq!(copy.pop(), Some(3));
+        assert_eq!(copy.pop(), Some(1));
+        assert_eq!(copy.min(), Some(&4));
+        assert_eq!(stack.min(), Some(&1));
+    }
+}
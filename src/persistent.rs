@@ -0,0 +1,619 @@
+//! An immutable stack whose versions share their nodes.
+//!
+//! The stack is generic over the reference-counted pointer that links its
+//! nodes: [`PersistentStack<T>`] uses `Rc` and [`ArcStack<T>`] uses `Arc`,
+//! i.e., both share one implementation.
+//!
+//! The mutable [`crate::GenericStack`] does *not* share this
+//! implementation, i.e., its link type is not a parameter: its nodes are
+//! owned by exactly one stack, linked in both directions, recycled in a
+//! node pool, and allocated with a custom allocator, none of which fits a
+//! `Box`, `Rc`, or `Arc` link. Hence, the pointer parameter is limited to
+//! the persistent stacks, and only the test suite of the operations all
+//! three stacks have in common is shared.
+
+use crate::display::fmt_head_list;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::fmt::{self, Debug, Display};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// `SharedPointer` selects the reference-counted pointer which links the
+/// nodes of a [`PersistentStack`], e.g., [`RcPointer`] or [`ArcPointer`].
+/// The implementing type is only a marker, it is never instantiated.
+///
+/// The links are raw pointers like the ones of [`Rc::into_raw`]: a link of
+/// an associated pointer type (e.g., `Rc<Node<T>>`) would make the stack
+/// invariant in `T`.
+///
+/// # Safety
+///
+/// The functions must implement a reference count: the value returned by
+/// `into_raw` must stay valid until `into_inner` was called once more than
+/// `increment_count`, and only the last call of `into_inner` may return the
+/// value.
+pub unsafe trait SharedPointer {
+    /// moves `value` to the heap and returns a pointer holding the first
+    /// reference to it, like [`Rc::into_raw`].
+    fn into_raw<U>(value: U) -> NonNull<U>;
+
+    /// adds a reference to the value of `pointer`, like
+    /// [`Rc::increment_strong_count`].
+    ///
+    /// # Safety
+    ///
+    /// `pointer` must have been returned by `into_raw` of the same pointer type
+    /// and must still hold a reference.
+    unsafe fn increment_count<U>(pointer: NonNull<U>);
+
+    /// gives up one reference and returns the value if it was the last
+    /// reference, like [`Rc::into_inner`].
+    ///
+    /// # Safety
+    ///
+    /// `pointer` must have been returned by `into_raw` of the same pointer type,
+    /// and the reference must not be used afterwards.
+    unsafe fn into_inner<U>(pointer: NonNull<U>) -> Option<U>;
+}
+
+/// links the nodes with [`Rc`], i.e., the stack stays on one thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RcPointer {}
+
+// SAFETY: the reference count of `Rc`
+unsafe impl SharedPointer for RcPointer {
+    fn into_raw<U>(value: U) -> NonNull<U> {
+        // SAFETY: `Rc::into_raw` does not return a null pointer
+        unsafe { NonNull::new_unchecked(Rc::into_raw(Rc::new(value)).cast_mut()) }
+    }
+
+    unsafe fn increment_count<U>(pointer: NonNull<U>) {
+        Rc::increment_strong_count(pointer.as_ptr());
+    }
+
+    unsafe fn into_inner<U>(pointer: NonNull<U>) -> Option<U> {
+        Rc::into_inner(Rc::from_raw(pointer.as_ptr()))
+    }
+}
+
+/// links the nodes with [`Arc`], i.e., the stack can be shared across
+/// threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArcPointer {}
+
+// SAFETY: the reference count of `Arc`
+unsafe impl SharedPointer for ArcPointer {
+    fn into_raw<U>(value: U) -> NonNull<U> {
+        // SAFETY: `Arc::into_raw` does not return a null pointer
+        unsafe { NonNull::new_unchecked(Arc::into_raw(Arc::new(value)).cast_mut()) }
+    }
+
+    unsafe fn increment_count<U>(pointer: NonNull<U>) {
+        Arc::increment_strong_count(pointer.as_ptr());
+    }
+
+    unsafe fn into_inner<U>(pointer: NonNull<U>) -> Option<U> {
+        Arc::into_inner(Arc::from_raw(pointer.as_ptr()))
+    }
+}
+
+/// `PersistentStack<T>` is an immutable stack, i.e., a functional cons
+/// list: `push` and `pop` do not modify a stack but return a new stack.
+/// The new stack shares the nodes below its top with the old stack with
+/// the help of [`Rc`], i.e., `push`, `pop`, and `clone` are O(1) and no
+/// elements are cloned. This permits to keep cheap snapshots, e.g., for
+/// backtracking algorithms.
+///
+/// The parameter `P` selects the pointer between the nodes; see
+/// [`ArcStack`] for the thread-safe variant.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::PersistentStack;
+/// let empty: PersistentStack<i32> = PersistentStack::new();
+/// let one = empty.push(1);
+/// let two = one.push(2);
+/// // `one` is a snapshot which is not changed by pushing on `two`
+/// assert_eq!(one.peek(), Some(&1));
+/// assert_eq!(two.peek(), Some(&2));
+///
+/// let (top, rest) = two.pop().unwrap();
+/// assert_eq!(*top, 2);
+/// assert_eq!(rest, one);
+/// ```
+pub struct PersistentStack<T, P: SharedPointer = RcPointer> {
+    head: Link<T>,
+    len: usize,
+    /// the stack owns the nodes (and elements) it does not share
+    marker: PhantomData<(Node<T>, P)>,
+}
+
+/// `ArcStack<T>` is the thread-safe variant of [`PersistentStack`]: its
+/// versions share their nodes with the help of [`Arc`] instead of `Rc`.
+/// `push`, `pop`, and `clone` are O(1), and an `ArcStack<T>` is [`Send`]
+/// and [`Sync`] if `T` is, i.e., it can be shared immutably with other
+/// threads, e.g., to propagate a stack of scopes into worker tasks.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::ArcStack;
+/// use std::thread;
+///
+/// let scopes = ArcStack::new().push("global").push("module");
+/// let workers: Vec<_> = (0..2)
+///     .map(|i| {
+///         let scopes = scopes.clone();
+///         thread::spawn(move || scopes.push(if i == 0 { "a" } else { "b" }).len())
+///     })
+///     .collect();
+/// for worker in workers {
+///     assert_eq!(worker.join().unwrap(), 3);
+/// }
+/// assert_eq!(scopes.peek(), Some(&"module"));
+/// ```
+pub type ArcStack<T> = PersistentStack<T, ArcPointer>;
+
+/// Each link holds one reference to its node, which was created with
+/// [`SharedPointer::into_raw`] of the pointer of the stack. A node is only
+/// borrowed while a stack holding a reference to it is borrowed.
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    element: T,
+    next: Link<T>,
+}
+
+impl<T, P: SharedPointer> PersistentStack<T, P> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub const fn new() -> Self {
+        PersistentStack {
+            head: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a new stack with `element` on the top of this stack. The new
+    /// stack shares all nodes with this stack.
+    #[must_use]
+    pub fn push(&self, element: T) -> Self {
+        PersistentStack {
+            head: Some(P::into_raw(Node {
+                element,
+                // SAFETY: the head of this stack
+                next: unsafe { Self::share(self.head) },
+            })),
+            len: self.len + 1,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the top element and the stack below the top element if this
+    /// stack is not empty. The returned stack shares all nodes with this stack.
+    #[must_use]
+    pub fn pop(&self) -> Option<(&T, Self)> {
+        self.top().map(|node| {
+            let rest = PersistentStack {
+                // SAFETY: the node is kept alive by this stack
+                head: unsafe { Self::share(node.next) },
+                len: self.len - 1,
+                marker: PhantomData,
+            };
+            (&node.element, rest)
+        })
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.top().map(|node| &node.element)
+    }
+
+    /// iterator from the top to the bottom of the stack
+    #[must_use]
+    pub fn iter(&self) -> PersistentIter<'_, T, P> {
+        PersistentIter {
+            next: self.top(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// borrows the top node of the stack
+    fn top(&self) -> Option<&Node<T>> {
+        // SAFETY: the stack holds a reference to its top node
+        self.head.map(|node| unsafe { &*node.as_ptr() })
+    }
+
+    /// adds a reference to the node of `link` for a new owner of the link.
+    ///
+    /// # Safety
+    ///
+    /// `link` must hold a reference to its node, e.g., it is the head of a
+    /// stack with pointer `P` or the link of a node of such a stack.
+    unsafe fn share(link: Link<T>) -> Link<T> {
+        if let Some(node) = link {
+            P::increment_count(node);
+        }
+        link
+    }
+}
+
+/// Cloning a [`PersistentStack`] only increments the reference count of
+/// the top node, i.e., `T` does not need to implement [`Clone`].
+impl<T, P: SharedPointer> Clone for PersistentStack<T, P> {
+    fn clone(&self) -> Self {
+        PersistentStack {
+            // SAFETY: the head of this stack
+            head: unsafe { Self::share(self.head) },
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, P: SharedPointer> Default for PersistentStack<T, P> {
+    fn default() -> Self {
+        PersistentStack::new()
+    }
+}
+
+/// We drop the nodes that are not shared with other stacks in a loop to
+/// avoid a recursive drop, which could overflow the call stack. We stop at
+/// the first node that is still shared. `into_inner` (rather than
+/// `try_unwrap`) guarantees that exactly one of several threads dropping
+/// their last `ArcStack` at the same time takes over a node.
+impl<T, P: SharedPointer> Drop for PersistentStack<T, P> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        // SAFETY: each link holds one reference to its node, which we give up
+        while let Some(node) = link.and_then(|node| unsafe { P::into_inner(node) }) {
+            link = node.next;
+        }
+    }
+}
+
+impl<T: Debug, P: SharedPointer> Debug for PersistentStack<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, P: SharedPointer> PartialEq for PersistentStack<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: SharedPointer> Eq for PersistentStack<T, P> {}
+
+/// [`PersistentStack`] prints its elements in the same format as
+/// [`crate::GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Display, P: SharedPointer> Display for PersistentStack<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self)
+    }
+}
+
+/// Creates a stack from an iterator: the last item ends up on the top.
+impl<T, P: SharedPointer> FromIterator<T> for PersistentStack<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = PersistentStack::new();
+        for element in iter {
+            stack = stack.push(element);
+        }
+        stack
+    }
+}
+
+impl<'a, T, P: SharedPointer> IntoIterator for &'a PersistentStack<T, P> {
+    type Item = &'a T;
+    type IntoIter = PersistentIter<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a [`PersistentStack`] from the top to the bottom.
+pub struct PersistentIter<'a, T, P: SharedPointer = RcPointer> {
+    next: Option<&'a Node<T>>,
+    marker: PhantomData<P>,
+}
+
+/// Iterator over the elements of an [`ArcStack`] from the top to the bottom.
+pub type ArcIter<'a, T> = PersistentIter<'a, T, ArcPointer>;
+
+impl<'a, T, P: SharedPointer> Iterator for PersistentIter<'a, T, P> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            // SAFETY: the node is kept alive by the node above it
+            self.next = node.next.map(|next| unsafe { &*next.as_ptr() });
+            &node.element
+        })
+    }
+}
+
+impl<T, P: SharedPointer> FusedIterator for PersistentIter<'_, T, P> {}
+
+// SAFETY: the nodes of an `ArcStack` are shared with the help of `Arc`,
+// i.e., the stack and its iterator can be sent and shared across threads
+// under the same conditions as an `Arc<T>`. The nodes of a
+// `PersistentStack` with `Rc` (or another pointer) stay on one thread.
+unsafe impl<T: Send + Sync> Send for PersistentStack<T, ArcPointer> {}
+unsafe impl<T: Send + Sync> Sync for PersistentStack<T, ArcPointer> {}
+unsafe impl<T: Send + Sync> Send for PersistentIter<'_, T, ArcPointer> {}
+unsafe impl<T: Send + Sync> Sync for PersistentIter<'_, T, ArcPointer> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GenericStack;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::cell::Cell;
+
+    assert_impl_all!(ArcStack<i32>: Send, Sync);
+    assert_impl_all!(ArcIter<'static, i32>: Send, Sync);
+    assert_not_impl_any!(ArcStack<Rc<i32>>: Send, Sync);
+    assert_not_impl_any!(ArcStack<Cell<i32>>: Send, Sync);
+    assert_not_impl_any!(PersistentStack<i32>: Send, Sync);
+    assert_not_impl_any!(PersistentIter<'static, i32>: Send, Sync);
+
+    #[test]
+    fn covariance() {
+        // like `GenericStack<T>`, the persistent stacks and their iterators
+        // are covariant in `T`
+        fn shorten<'a>(stack: PersistentStack<&'static str>) -> PersistentStack<&'a str> {
+            stack
+        }
+        fn shorten_arc<'a>(stack: ArcStack<&'static str>) -> ArcStack<&'a str> {
+            stack
+        }
+        fn shorten_iter<'a, 'b>(
+            iter: PersistentIter<'a, &'static str>,
+        ) -> PersistentIter<'a, &'b str> {
+            iter
+        }
+
+        let local = String::from("b");
+        let stack = shorten(PersistentStack::new().push("a")).push(&local);
+        assert_eq!(stack.len(), 2);
+        let stack = shorten_arc(ArcStack::new().push("a")).push(&local);
+        assert_eq!(stack.peek(), Some(&"b"));
+        let static_stack = PersistentStack::new().push("c");
+        assert_eq!(shorten_iter(static_stack.iter()).next(), Some(&"c"));
+    }
+
+    /// runs a test for both pointers
+    macro_rules! for_each_pointer {
+        ($($test:ident),*) => {
+            $(
+                mod $test {
+                    #[test]
+                    fn rc() {
+                        super::$test::<super::RcPointer>();
+                    }
+
+                    #[test]
+                    fn arc() {
+                        super::$test::<super::ArcPointer>();
+                    }
+                }
+            )*
+        };
+    }
+
+    for_each_pointer!(basics, sharing, drop_deep_stack);
+
+    /// the operations that [`GenericStack`], [`PersistentStack`], and
+    /// [`ArcStack`] have in common: the persistent stacks replace `self`
+    /// with the new version
+    trait CommonStack: FromIterator<i32> + Display + Debug + PartialEq {
+        fn empty() -> Self;
+        fn push_element(&mut self, element: i32);
+        fn pop_element(&mut self) -> Option<i32>;
+        fn top(&self) -> Option<&i32>;
+        fn length(&self) -> usize;
+        fn elements(&self) -> Vec<i32>;
+    }
+
+    impl CommonStack for GenericStack<i32> {
+        fn empty() -> Self {
+            GenericStack::new()
+        }
+
+        fn push_element(&mut self, element: i32) {
+            self.push(element);
+        }
+
+        fn pop_element(&mut self) -> Option<i32> {
+            self.pop()
+        }
+
+        fn top(&self) -> Option<&i32> {
+            self.peek()
+        }
+
+        fn length(&self) -> usize {
+            self.len()
+        }
+
+        fn elements(&self) -> Vec<i32> {
+            self.iter().copied().collect()
+        }
+    }
+
+    impl<P: SharedPointer> CommonStack for PersistentStack<i32, P> {
+        fn empty() -> Self {
+            PersistentStack::new()
+        }
+
+        fn push_element(&mut self, element: i32) {
+            *self = self.push(element);
+        }
+
+        fn pop_element(&mut self) -> Option<i32> {
+            let (top, rest) = self.pop()?;
+            let top = *top;
+            *self = rest;
+            Some(top)
+        }
+
+        fn top(&self) -> Option<&i32> {
+            self.peek()
+        }
+
+        fn length(&self) -> usize {
+            self.len()
+        }
+
+        fn elements(&self) -> Vec<i32> {
+            self.iter().copied().collect()
+        }
+    }
+
+    /// runs a test of the common test suite for all three stacks
+    macro_rules! for_each_stack {
+        ($($test:ident),*) => {
+            $(
+                mod $test {
+                    #[test]
+                    fn generic() {
+                        super::$test::<crate::GenericStack<i32>>();
+                    }
+
+                    #[test]
+                    fn rc() {
+                        super::$test::<super::PersistentStack<i32>>();
+                    }
+
+                    #[test]
+                    fn arc() {
+                        super::$test::<super::ArcStack<i32>>();
+                    }
+                }
+            )*
+        };
+    }
+
+    for_each_stack!(common_push_pop, common_conversions, common_deep_stack);
+
+    fn common_push_pop<S: CommonStack>() {
+        let mut stack = S::empty();
+        assert_eq!(stack.pop_element(), None);
+        assert_eq!(stack.top(), None);
+        for i in 0..5 {
+            stack.push_element(i);
+            assert_eq!(stack.top(), Some(&i));
+            assert_eq!(stack.length(), (i + 1) as usize);
+        }
+        assert_eq!(stack.pop_element(), Some(4));
+        assert_eq!(stack.pop_element(), Some(3));
+        stack.push_element(7);
+        assert_eq!(stack.elements(), vec![7, 2, 1, 0]);
+        while stack.pop_element().is_some() {}
+        assert_eq!(stack.length(), 0);
+        assert_eq!(stack, S::empty());
+    }
+
+    fn common_conversions<S: CommonStack>() {
+        let stack: S = (1..=3).collect();
+        assert_eq!(stack.elements(), vec![3, 2, 1]);
+        assert_eq!(stack.to_string(), "head->3->2->1.");
+        assert_ne!(stack, (1..=2).collect());
+        assert_eq!(S::empty().to_string(), "head.");
+    }
+
+    fn common_deep_stack<S: CommonStack>() {
+        // Miri is too slow for a million nodes
+        let depth = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let mut stack: S = (0..depth).collect();
+        assert_eq!(stack.length(), depth as usize);
+        assert_eq!(stack.pop_element(), Some(depth - 1));
+        drop(stack);
+    }
+
+    fn basics<P: SharedPointer>() {
+        let empty: PersistentStack<i32, P> = PersistentStack::new();
+        assert!(empty.pop().is_none());
+        assert_eq!(empty.peek(), None);
+
+        let a = empty.push(1).push(2).push(3);
+        assert_eq!(a.len(), 3);
+        let (top, b) = a.pop().unwrap();
+        assert_eq!(*top, 3);
+        assert_eq!(b.len(), 2);
+        let c = b.push(4);
+
+        // all versions remain valid
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(c.iter().copied().collect::<Vec<_>>(), vec![4, 2, 1]);
+        assert_eq!(c.to_string(), "head->4->2->1.");
+        assert_eq!(c, [1, 2, 4].into_iter().collect());
+        assert!(empty.is_empty());
+    }
+
+    fn sharing<P: SharedPointer>() {
+        let counter = Rc::new(());
+        let base: PersistentStack<_, P> = (0..3).map(|_| Rc::clone(&counter)).collect();
+        let snapshot = base.clone();
+        let extended = base.push(Rc::clone(&counter));
+        // the elements of `base` are not cloned
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        drop(base);
+        drop(extended);
+        assert_eq!(Rc::strong_count(&counter), 4);
+        assert_eq!(snapshot.len(), 3);
+        drop(snapshot);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    fn drop_deep_stack<P: SharedPointer>() {
+        // Miri is too slow for a million nodes
+        let depth = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        let stack: PersistentStack<_, P> = (0..depth).collect();
+        let shared = stack.pop().unwrap().1;
+        drop(stack);
+        assert_eq!(shared.len(), depth - 1);
+        drop(shared);
+    }
+
+    #[test]
+    fn sharing_across_threads() {
+        let counter = Arc::new(());
+        let base: ArcStack<_> = (0..3).map(|_| Arc::clone(&counter)).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let base = base.clone();
+                let counter = Arc::clone(&counter);
+                std::thread::spawn(move || base.push(counter).len())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 4);
+        }
+        // the elements of `base` are not cloned
+        assert_eq!(Arc::strong_count(&counter), 4);
+        drop(base);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,269 @@
+//! A stack whose elements are pinned.
+
+use crate::{Allocator, GenericStack, Global, Iter};
+use core::fmt::{self, Debug};
+use core::pin::Pin;
+use core::ptr;
+
+/// `PinnedStack<T>` is a [`GenericStack<T>`] which pins its elements: an
+/// element never moves in memory from `push_pinned` until it is dropped,
+/// i.e., self-referential futures or state machines can live on the stack
+/// and be accessed with [`PinnedStack::peek_pinned_mut`].
+///
+/// Each element lives in its own node, whose address does not change when
+/// other elements are pushed or popped. `GenericStack` cannot give this
+/// guarantee itself, because `pop` returns the elements by value and
+/// `peek_mut` permits to move them, e.g., with `mem::swap`. Hence, `PinnedStack` drops the top element in place with
+/// [`PinnedStack::drop_top`], and only returns elements by value if they
+/// are [`Unpin`].
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::PinnedStack;
+/// use std::marker::PhantomPinned;
+/// use std::pin::Pin;
+///
+/// struct Frame {
+///     value: u32,
+///     _pinned: PhantomPinned,
+/// }
+///
+/// let mut stack = PinnedStack::new();
+/// let frame: Pin<&mut Frame> = stack.push_pinned(Frame { value: 1, _pinned: PhantomPinned });
+/// let address: *const Frame = &*frame;
+/// stack.push_pinned(Frame { value: 2, _pinned: PhantomPinned });
+/// assert!(stack.drop_top());
+///
+/// let top = stack.peek_pinned().unwrap();
+/// assert_eq!(top.value, 1);
+/// assert_eq!(&*top as *const Frame, address);
+/// ```
+pub struct PinnedStack<T, A: Allocator = Global> {
+    stack: GenericStack<T, A>,
+}
+
+impl<T> PinnedStack<T> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        PinnedStack {
+            stack: GenericStack::new(),
+        }
+    }
+}
+
+impl<T, A: Allocator> PinnedStack<T, A> {
+    /// Create a new, empty stack whose nodes are allocated with `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        PinnedStack {
+            stack: GenericStack::new_in(alloc),
+        }
+    }
+
+    /// push a new element on the top of the stack and returns it pinned.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn push_pinned(&mut self, element: T) -> Pin<&mut T> {
+        self.stack.push(element);
+        self.peek_pinned_mut()
+            .expect("the stack is not empty after a push")
+    }
+
+    /// borrows the pinned top element of the stack if the stack is not
+    /// empty.
+    pub fn peek_pinned(&self) -> Option<Pin<&T>> {
+        // SAFETY: the element is not moved until it is dropped in place
+        self.stack
+            .peek()
+            .map(|element| unsafe { Pin::new_unchecked(element) })
+    }
+
+    /// mutably borrows the pinned top element of the stack if the stack is
+    /// not empty.
+    pub fn peek_pinned_mut(&mut self) -> Option<Pin<&mut T>> {
+        // SAFETY: the element is not moved until it is dropped in place
+        self.stack
+            .peek_mut()
+            .map(|element| unsafe { Pin::new_unchecked(element) })
+    }
+
+    /// drops the top element of the stack in place. Returns `false` if the
+    /// stack is empty.
+    pub fn drop_top(&mut self) -> bool {
+        match self.stack.pop_node() {
+            Some(node) => {
+                // SAFETY: the node was allocated by the stack, is unlinked,
+                // and its element is initialized
+                unsafe { self.stack.drop_node(node) };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// drops all elements of the stack in place from the top to the
+    /// bottom.
+    pub fn clear(&mut self) {
+        while self.drop_top() {}
+    }
+
+    /// iterator from the top to the bottom of the stack. Shared references
+    /// do not permit to move the elements.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.stack.iter()
+    }
+
+    /// Returns the number of elements stored on the stack.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+/// Elements which are [`Unpin`] may be moved after all.
+impl<T: Unpin, A: Allocator> PinnedStack<T, A> {
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// Returns the underlying [`GenericStack`].
+    pub fn into_inner(self) -> GenericStack<T, A> {
+        let this = core::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is not used (or dropped) afterwards
+        unsafe { ptr::read(ptr::addr_of!(this.stack)) }
+    }
+}
+
+impl<T> Default for PinnedStack<T> {
+    fn default() -> Self {
+        PinnedStack::new()
+    }
+}
+
+/// Moving the elements of a [`GenericStack`] into a `PinnedStack` is fine:
+/// they were not pinned before.
+impl<T, A: Allocator> From<GenericStack<T, A>> for PinnedStack<T, A> {
+    fn from(stack: GenericStack<T, A>) -> Self {
+        PinnedStack { stack }
+    }
+}
+
+/// The elements are dropped in place from the top to the bottom before
+/// the nodes are deallocated. If the drop of an element panics, the
+/// remaining elements are dropped in place when the inner stack is dropped.
+impl<T, A: Allocator> Drop for PinnedStack<T, A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for PinnedStack<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.stack, f)
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a PinnedStack<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::marker::PhantomPinned;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// remembers its own address when pinned and records its drop
+    struct SelfAware {
+        id: u32,
+        address: Option<*const SelfAware>,
+        drops: Rc<RefCell<Vec<u32>>>,
+        panics: bool,
+        _pinned: PhantomPinned,
+    }
+
+    impl SelfAware {
+        fn new(id: u32, drops: &Rc<RefCell<Vec<u32>>>) -> Self {
+            SelfAware {
+                id,
+                address: None,
+                drops: Rc::clone(drops),
+                panics: false,
+                _pinned: PhantomPinned,
+            }
+        }
+
+        fn init(self: Pin<&mut Self>) {
+            let address: *const SelfAware = &*self;
+            // SAFETY: only a field is written, nothing is moved
+            unsafe { self.get_unchecked_mut().address = Some(address) };
+        }
+    }
+
+    impl Drop for SelfAware {
+        fn drop(&mut self) {
+            // the element must still be at the address it was pinned at
+            assert_eq!(self.address, Some(self as *const SelfAware));
+            self.drops.borrow_mut().push(self.id);
+            assert!(!self.panics, "drop of element {}", self.id);
+        }
+    }
+
+    #[test]
+    fn pinned_elements_do_not_move() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+        let mut stack = PinnedStack::new();
+        for id in 0..4 {
+            stack.push_pinned(SelfAware::new(id, &drops)).init();
+        }
+        assert_eq!(stack.len(), 4);
+        assert_eq!(stack.peek_pinned().unwrap().id, 3);
+        assert!(stack.drop_top());
+        stack.push_pinned(SelfAware::new(4, &drops)).init();
+        assert_eq!(
+            stack.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![4, 2, 1, 0]
+        );
+        drop(stack);
+        assert_eq!(*drops.borrow(), vec![3, 4, 2, 1, 0]);
+    }
+
+    #[test]
+    fn panicking_drop() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+        let mut stack = PinnedStack::new();
+        for id in 0..4 {
+            let mut element = SelfAware::new(id, &drops);
+            element.panics = id == 2;
+            stack.push_pinned(element).init();
+        }
+        // the elements below the panicking one must not move either
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(stack)));
+        assert!(result.is_err());
+        assert_eq!(*drops.borrow(), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn unpin_elements() {
+        let mut stack = PinnedStack::from(GenericStack::from(vec![1, 2]));
+        *stack.peek_pinned_mut().unwrap() += 10;
+        assert_eq!(stack.pop(), Some(12));
+        stack.push_pinned(3);
+        assert_eq!(
+            stack.into_inner().into_iter().collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+        assert!(!PinnedStack::<u8>::new().drop_top());
+    }
+}
@@ -0,0 +1,120 @@
+//! Python bindings of the stack generated by PyO3.
+
+use crate::GenericStack;
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+
+/// `PyStack` exports a [`GenericStack`] of Python objects as the class
+/// `ll_stack.Stack`. Build the module with `maturin develop` (see
+/// `pyproject.toml`) and use it like a Python container:
+///
+/// ```python
+/// from ll_stack import Stack
+/// stack = Stack()
+/// stack.push(1)
+/// stack.push("two")
+/// assert len(stack) == 2
+/// assert list(stack) == ["two", 1]
+/// assert stack.pop() == "two"
+/// ```
+///
+/// The stack stores references to the objects, i.e., it does not copy
+/// them. Like `list.pop`, `pop` raises an `IndexError` if the stack is
+/// empty, whereas `peek` returns `None`.
+#[pyclass(name = "Stack", module = "ll_stack")]
+pub struct PyStack {
+    stack: GenericStack<PyObject>,
+}
+
+#[pymethods]
+impl PyStack {
+    /// Create a new, empty stack.
+    #[new]
+    pub fn new() -> Self {
+        PyStack {
+            stack: GenericStack::new(),
+        }
+    }
+
+    /// push a new object on the top of the stack.
+    pub fn push(&mut self, value: PyObject) {
+        self.stack.push(value);
+    }
+
+    /// Removes and returns the top object of the stack.
+    ///
+    /// # Errors
+    ///
+    /// Raises an `IndexError` if the stack is empty.
+    pub fn pop(&mut self) -> PyResult<PyObject> {
+        self.stack
+            .pop()
+            .ok_or_else(|| PyIndexError::new_err("pop from empty stack"))
+    }
+
+    /// Returns the top object of the stack, or `None` if the stack is empty.
+    pub fn peek(&self, py: Python<'_>) -> Option<PyObject> {
+        self.stack.peek().map(|value| value.clone_ref(py))
+    }
+
+    /// Removes all objects from the stack.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// iterates over a snapshot of the stack from the top to the bottom,
+    /// i.e., modifying the stack does not invalidate the iterator.
+    fn __iter__(&self, py: Python<'_>) -> PyStackIter {
+        let elements: Vec<PyObject> = self.stack.iter().map(|value| value.clone_ref(py)).collect();
+        PyStackIter {
+            elements: elements.into_iter(),
+        }
+    }
+
+    /// e.g., `Stack([3, 2, 1])` with the top on the left
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let elements = self
+            .stack
+            .iter()
+            .map(|value| Ok(value.bind(py).repr()?.to_string()))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!("Stack([{}])", elements.join(", ")))
+    }
+}
+
+impl Default for PyStack {
+    fn default() -> Self {
+        PyStack::new()
+    }
+}
+
+/// The iterator returned by `iter(stack)` in Python.
+#[pyclass(name = "StackIterator", module = "ll_stack")]
+pub struct PyStackIter {
+    elements: vec::IntoIter<PyObject>,
+}
+
+#[pymethods]
+impl PyStackIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<PyObject> {
+        self.elements.next()
+    }
+}
+
+/// The Python module `ll_stack`.
+#[pymodule]
+fn ll_stack(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyStack>()?;
+    module.add_class::<PyStackIter>()?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! A FIFO queue built from two stacks.
+
+use crate::GenericStack;
+use core::fmt::{self, Debug};
+
+/// `TwoStackQueue<T>` is a first-in first-out queue built from two
+/// [`GenericStack<T>`]s: new elements are pushed on the back stack, and
+/// elements are popped from the front stack. If the front stack is empty,
+/// all elements of the back stack are moved to the front stack, which
+/// reverses their order, i.e., the oldest element ends up on the top.
+///
+/// Each element is moved exactly once from the back to the front stack,
+/// i.e., [`TwoStackQueue::enqueue`] and [`TwoStackQueue::dequeue`] take
+/// amortized O(1) time.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::TwoStackQueue;
+/// let mut queue = TwoStackQueue::new();
+/// queue.enqueue(1);
+/// queue.enqueue(2);
+/// assert_eq!(queue.dequeue(), Some(1));
+/// queue.enqueue(3);
+/// assert_eq!(queue.dequeue(), Some(2));
+/// assert_eq!(queue.dequeue(), Some(3));
+/// assert_eq!(queue.dequeue(), None);
+/// ```
+#[derive(Clone)]
+pub struct TwoStackQueue<T> {
+    /// the oldest elements, the oldest one on the top
+    front: GenericStack<T>,
+    /// the newest elements, the newest one on the top
+    back: GenericStack<T>,
+}
+
+impl<T> TwoStackQueue<T> {
+    /// Create a new, empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        TwoStackQueue {
+            front: GenericStack::new(),
+            back: GenericStack::new(),
+        }
+    }
+
+    /// appends `element` at the back of the queue.
+    pub fn enqueue(&mut self, element: T) {
+        self.back.push(element);
+    }
+
+    /// Removes and returns the element at the front of the queue, i.e., the
+    /// oldest element, if the queue is not empty.
+    pub fn dequeue(&mut self) -> Option<T> {
+        if self.front.is_empty() {
+            // popping from the back stack returns the newest element first,
+            // hence the oldest element is pushed last
+            while let Some(element) = self.back.pop() {
+                self.front.push(element);
+            }
+        }
+        self.front.pop()
+    }
+
+    /// borrows the element at the front of the queue. If the front stack is
+    /// empty, this is the bottom element of the back stack.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.front.peek().or_else(|| self.back.peek_bottom())
+    }
+
+    /// iterator from the front to the back of the queue
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.front.iter().chain(self.back.iter().rev())
+    }
+
+    /// Returns the number of elements stored in the queue.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Returns `true` if the queue does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+}
+
+impl<T> Default for TwoStackQueue<T> {
+    fn default() -> Self {
+        TwoStackQueue::new()
+    }
+}
+
+/// The queue is printed from the front to the back, independent of how the
+/// elements are distributed on the two stacks.
+impl<T: Debug> Debug for TwoStackQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Two queues are equal if they contain the same elements in the same
+/// order, independent of how the elements are distributed on the two
+/// stacks.
+impl<T: PartialEq> PartialEq for TwoStackQueue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for TwoStackQueue<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::format;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn fifo() {
+        let mut queue = TwoStackQueue::new();
+        assert_eq!(queue.peek(), None);
+        for v in 1..=3 {
+            queue.enqueue(v);
+        }
+        assert_eq!(queue.peek(), Some(&1));
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(4);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.peek(), Some(&2));
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(
+            queue.iter().rev().copied().collect::<Vec<_>>(),
+            vec![4, 3, 2]
+        );
+        assert_eq!(format!("{queue:?}"), "[2, 3, 4]");
+
+        let mut dequeued = Vec::new();
+        while let Some(v) = queue.dequeue() {
+            dequeued.push(v);
+        }
+        assert_eq!(dequeued, vec![2, 3, 4]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn eq() {
+        let mut a = TwoStackQueue::new();
+        let mut b = TwoStackQueue::new();
+        for v in 0..3 {
+            a.enqueue(v);
+        }
+        // the elements of `a` are now on the front stack
+        assert_eq!(a.dequeue(), Some(0));
+        a.enqueue(3);
+        // the elements of `b` are on the back stack
+        for v in 1..=3 {
+            b.enqueue(v);
+        }
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), b);
+        b.enqueue(4);
+        assert_ne!(a, b);
+        assert_eq!(TwoStackQueue::<u8>::default(), TwoStackQueue::new());
+    }
+}
@@ -0,0 +1,98 @@
+//! Parallel iterators of crate `rayon` over [`GenericStack<T>`].
+//!
+//! A linked list cannot be split in the middle without walking to the
+//! middle, i.e., it does not fit the divide-and-conquer approach of rayon.
+//! Hence, we snapshot the elements (or references to the elements) into a
+//! `Vec` in a single pass and let rayon split the vector. This pays off if
+//! the work per element is much more expensive than copying a pointer.
+//!
+//! All parallel iterators are indexed and yield the elements from the top
+//! to the bottom, i.e., in the same order as [`GenericStack::iter`].
+//!
+//! # Example
+//!
+//! ```
+//! use ll_stack::GenericStack;
+//! use rayon::prelude::*;
+//!
+//! let mut stack: GenericStack<u64> = (1..=100).collect();
+//! stack.par_iter_mut().for_each(|v| *v *= 2);
+//! let sum: u64 = stack.par_iter().map(|v| v * v).sum();
+//! assert_eq!(sum, 4 * 338_350);
+//! ```
+
+use crate::{Allocator, GenericStack, Global};
+use alloc::vec::Vec;
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend};
+use rayon::vec::IntoIter;
+
+/// Moves the elements into a `Vec` from the top to the bottom.
+impl<T: Send, A: Allocator> IntoParallelIterator for GenericStack<T, A> {
+    type Iter = IntoIter<T>;
+    type Item = T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.into_iter().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+/// Enables `stack.par_iter()`.
+impl<'a, T: Sync, A: Allocator> IntoParallelIterator for &'a GenericStack<T, A> {
+    type Iter = IntoIter<&'a T>;
+    type Item = &'a T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.iter().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+/// Enables `stack.par_iter_mut()`.
+impl<'a, T: Send, A: Allocator> IntoParallelIterator for &'a mut GenericStack<T, A> {
+    type Iter = IntoIter<&'a mut T>;
+    type Item = &'a mut T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.iter_mut().collect::<Vec<_>>().into_par_iter()
+    }
+}
+
+/// Pushes the items in the order of the parallel iterator, like
+/// [`Extend`], i.e., the last item ends up on the top.
+impl<T: Send> ParallelExtend<T> for GenericStack<T, Global> {
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        let mut elements = Vec::new();
+        elements.par_extend(par_iter);
+        self.extend(elements);
+    }
+}
+
+/// Collects the items like [`FromIterator`], i.e., the last item ends up
+/// on the top.
+impl<T: Send> FromParallelIterator<T> for GenericStack<T, Global> {
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        let mut stack = GenericStack::new();
+        stack.par_extend(par_iter);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn par_iter() {
+        let mut stack: GenericStack<u32> = (0..1000).collect();
+        assert_eq!(stack.par_iter().count(), 1000);
+        assert_eq!(stack.par_iter().map(|&v| v as u64).sum::<u64>(), 499_500);
+
+        stack.par_iter_mut().for_each(|v| *v += 1);
+        let top_first: Vec<u32> = stack.par_iter().map(|&v| v).collect();
+        assert_eq!(top_first, (1..=1000).rev().collect::<Vec<_>>());
+
+        let squares: GenericStack<u32> = stack.into_par_iter().map(|v| v * v).collect();
+        assert_eq!(squares.peek(), Some(&1));
+        assert_eq!(squares.len(), 1000);
+    }
+}
@@ -0,0 +1,99 @@
+//! Optional support for [`rkyv`]: enable feature `rkyv` to archive a
+//! [`GenericStack<T>`] without deserialization copies.
+//!
+//! A stack is archived as a contiguous sequence from the bottom to the top
+//! (like with feature `serde`), i.e., the archived stack is an
+//! [`ArchivedStack<T>`] which can be inspected in place, e.g., after
+//! memory-mapping a file. Deserializing pushes the elements in the order of
+//! the sequence. Hence, a round trip preserves the LIFO order of the stack.
+//!
+//! # Example
+//!
+//! ```
+//! use ll_stack::{ArchivedStack, GenericStack};
+//! use rkyv::rancor::Error;
+//! let stack: GenericStack<u32> = (1..=3).collect();
+//! let bytes = rkyv::to_bytes::<Error>(&stack).unwrap();
+//! // SAFETY: the bytes were just produced by `to_bytes`
+//! let archived = unsafe { rkyv::access_unchecked::<ArchivedStack<u32>>(&bytes) };
+//! // the top of the stack is the last element of the archived sequence
+//! assert_eq!(archived.last().map(|v| v.to_native()), Some(3));
+//! let mut stack: GenericStack<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+//! assert_eq!(stack.pop(), Some(3));
+//! ```
+
+use crate::{Allocator, GenericStack};
+use rkyv::rancor::Fallible;
+use rkyv::ser::{Allocator as ScratchAllocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, Place, Serialize};
+
+/// The archived form of a [`GenericStack<T>`]: the elements from the
+/// bottom to the top of the stack.
+pub type ArchivedStack<T> = ArchivedVec<Archived<T>>;
+
+impl<T: Archive, A: Allocator> Archive for GenericStack<T, A> {
+    type Archived = ArchivedStack<T>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<T, A, S> Serialize<S> for GenericStack<T, A>
+where
+    T: Serialize<S>,
+    A: Allocator,
+    S: Fallible + ScratchAllocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // we iterate from the top to the bottom, but archive from the bottom to the top
+        ArchivedVec::serialize_from_iter::<T, _, _>(self.iter().rev(), serializer)
+    }
+}
+
+impl<T, A, D> Deserialize<GenericStack<T, A>, D> for ArchivedStack<T>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+    A: Allocator + Default,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<GenericStack<T, A>, D::Error> {
+        let mut stack = GenericStack::default();
+        for element in self.iter() {
+            stack.push(element.deserialize(deserializer)?);
+        }
+        Ok(stack)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ArchivedStack, GenericStack};
+    use rkyv::rancor::Error;
+
+    #[test]
+    fn round_trip() {
+        let stack: GenericStack<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let bytes = rkyv::to_bytes::<Error>(&stack).unwrap();
+        let archived = unsafe { rkyv::access_unchecked::<ArchivedStack<String>>(&bytes) };
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived[0].as_str(), "a");
+        assert_eq!(archived[2].as_str(), "c");
+
+        let decoded: GenericStack<String> = rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(decoded, stack);
+    }
+
+    #[test]
+    fn empty() {
+        let stack: GenericStack<u8> = GenericStack::new();
+        let bytes = rkyv::to_bytes::<Error>(&stack).unwrap();
+        let archived = unsafe { rkyv::access_unchecked::<ArchivedStack<u8>>(&bytes) };
+        assert!(archived.is_empty());
+        let decoded: GenericStack<u8> = rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert!(decoded.is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Optional support for [`serde`]: enable feature `serde` to serialize
+//! and deserialize a [`GenericStack<T>`].
+//!
+//! A stack is serialized as a sequence from the bottom to the top, i.e.,
+//! the last element of the sequence is the top of the stack. Deserializing
+//! pushes the elements in the order of the sequence. Hence, a round trip
+//! preserves the LIFO order of the stack.
+//!
+//! # Example
+//!
+//! ```
+//! use ll_stack::GenericStack;
+//! let stack: GenericStack<_> = (1..=3).collect();
+//! let json = serde_json::to_string(&stack).unwrap();
+//! assert_eq!(json, "[1,2,3]");
+//! let mut stack: GenericStack<u32> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(stack.pop(), Some(3));
+//! ```
+//!
+//! With feature `json`, the convenience methods [`GenericStack::to_json`]
+//! and [`GenericStack::from_json`] use the same format.
+
+use crate::{Allocator, GenericStack};
+#[cfg(feature = "json")]
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+#[cfg(feature = "json")]
+use serde::de::DeserializeOwned;
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+impl<T: Serialize, A: Allocator> Serialize for GenericStack<T, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // we iterate from the top to the bottom, but serialize from the bottom to the top
+        let elements: Vec<&T> = self.iter().collect();
+        serializer.collect_seq(elements.into_iter().rev())
+    }
+}
+
+struct StackVisitor<T, A>(PhantomData<(T, A)>);
+
+impl<'de, T: Deserialize<'de>, A: Allocator + Default> Visitor<'de> for StackVisitor<T, A> {
+    type Value = GenericStack<T, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of stack elements")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let mut stack = GenericStack::default();
+        while let Some(element) = seq.next_element()? {
+            stack.push(element);
+        }
+        Ok(stack)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, A: Allocator + Default> Deserialize<'de> for GenericStack<T, A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(StackVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: Serialize, A: Allocator> GenericStack<T, A> {
+    /// Serializes the stack as a JSON array from the bottom to the top.
+    ///
+    /// # Panics
+    ///
+    /// if an element cannot be represented in JSON, e.g., a map with
+    /// non-string keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let stack = GenericStack::from(vec![1, 2, 3]);
+    /// assert_eq!(stack.to_json(), "[1,2,3]");
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("stack elements can be serialized to JSON")
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: DeserializeOwned, A: Allocator + Default> GenericStack<T, A> {
+    /// Deserializes a stack from a JSON array: the last element of the
+    /// array becomes the top of the stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack: GenericStack<u32> = GenericStack::from_json("[1,2,3]").unwrap();
+    /// assert_eq!(stack.pop(), Some(3));
+    /// assert!(GenericStack::<u32>::from_json("{}").is_err());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::GenericStack;
+
+    #[test]
+    fn round_trip() {
+        let stack: GenericStack<_> = vec!["a", "b", "c"].into_iter().collect();
+        let json = serde_json::to_string(&stack).unwrap();
+        assert_eq!(json, r#"["a","b","c"]"#);
+
+        let decoded: GenericStack<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(
+            decoded.into_iter().collect::<Vec<_>>(),
+            vec!["c".to_string(), "b".to_string(), "a".to_string()]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let stack: GenericStack<u8> = GenericStack::new();
+        let json = serde_json::to_string(&stack).unwrap();
+        assert_eq!(json, "[]");
+        let decoded: GenericStack<u8> = serde_json::from_str(&json).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let stack = GenericStack::from(vec![vec![1], vec![2, 3]]);
+        let json = stack.to_json();
+        assert_eq!(json, "[[1],[2,3]]");
+        let decoded: GenericStack<Vec<u8>> = GenericStack::from_json(&json).unwrap();
+        assert_eq!(decoded, stack);
+        assert!(GenericStack::<u8>::from_json("[1, -1]").is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! A stack with shared ownership for a single thread.
+
+use crate::GenericStack;
+use alloc::rc::Rc;
+use core::cell::{Ref, RefCell, RefMut};
+use core::fmt::{self, Debug};
+
+/// `SharedStack<T>` is a [`GenericStack<T>`] with shared ownership: cloning
+/// a `SharedStack` is O(1) and returns another handle to the same stack,
+/// and all handles can push and pop through a shared reference. This is
+/// useful if several owners in a single thread modify the same stack,
+/// e.g., GUI callbacks or recursive visitors.
+///
+/// The stack is stored in an `Rc<RefCell<_>>`, i.e., the borrows are
+/// checked at runtime inside of this type. Methods that modify the stack
+/// panic if an element is borrowed with [`SharedStack::peek`] or
+/// [`SharedStack::peek_mut`] at the same time. Since [`Rc`] is not
+/// thread-safe, a `SharedStack` cannot be sent to another thread.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::SharedStack;
+/// let stack = SharedStack::new();
+/// let handle = stack.clone();
+/// stack.push(1);
+/// handle.push(2);
+/// assert_eq!(stack.len(), 2);
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(handle.pop(), Some(1));
+/// ```
+pub struct SharedStack<T> {
+    stack: Rc<RefCell<GenericStack<T>>>,
+}
+
+impl<T> SharedStack<T> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        SharedStack {
+            stack: Rc::new(RefCell::new(GenericStack::new())),
+        }
+    }
+
+    /// push a new element on the top element of the stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of the stack is currently borrowed.
+    pub fn push(&self, element: T) {
+        self.stack.borrow_mut().push(element);
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of the stack is currently borrowed.
+    #[allow(clippy::must_use_candidate)]
+    pub fn pop(&self) -> Option<T> {
+        self.stack.borrow_mut().pop()
+    }
+
+    /// borrows the top element of the stack if the stack is not empty. The
+    /// stack cannot be modified while the returned [`Ref`] exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the top element is currently borrowed mutably.
+    #[must_use]
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.stack.borrow(), super::GenericStack::peek).ok()
+    }
+
+    /// borrows the top element of the stack as a mutable value if the
+    /// stack is not empty. The stack cannot be used otherwise while the
+    /// returned [`RefMut`] exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of the stack is currently borrowed.
+    #[must_use]
+    pub fn peek_mut(&self) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.stack.borrow_mut(), super::GenericStack::peek_mut).ok()
+    }
+
+    /// calls `f` with the borrowed stack, e.g., to iterate over the
+    /// elements, and returns its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of the stack is currently borrowed mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::SharedStack;
+    /// let stack = SharedStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.with(|stack| stack.iter().sum::<i32>()), 3);
+    /// ```
+    pub fn with<R>(&self, f: impl FnOnce(&GenericStack<T>) -> R) -> R {
+        f(&self.stack.borrow())
+    }
+
+    /// calls `f` with the mutably borrowed stack, e.g., to use a method of
+    /// [`GenericStack`] that is not provided by `SharedStack`, and returns
+    /// its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element of the stack is currently borrowed, e.g., if
+    /// `f` uses another handle of the same stack.
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut GenericStack<T>) -> R) -> R {
+        f(&mut self.stack.borrow_mut())
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stack.borrow().len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stack.borrow().is_empty()
+    }
+
+    /// Removes all elements from the stack.
+    pub fn clear(&self) {
+        self.stack.borrow_mut().clear();
+    }
+
+    /// Returns `true` if both handles refer to the same stack.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.stack, &other.stack)
+    }
+
+    /// Returns a copy of the current contents of the stack, which is not
+    /// shared with any other handle.
+    #[must_use]
+    pub fn snapshot(&self) -> GenericStack<T>
+    where
+        T: Clone,
+    {
+        self.stack.borrow().clone()
+    }
+}
+
+impl<T> Default for SharedStack<T> {
+    fn default() -> Self {
+        SharedStack::new()
+    }
+}
+
+/// Cloning a [`SharedStack`] returns another handle to the same stack. Use
+/// [`SharedStack::snapshot`] to copy the elements.
+impl<T> Clone for SharedStack<T> {
+    fn clone(&self) -> Self {
+        SharedStack {
+            stack: Rc::clone(&self.stack),
+        }
+    }
+}
+
+/// Prints the elements from the top to the bottom, or `<borrowed>` if the
+/// stack is currently borrowed mutably.
+impl<T: Debug> Debug for SharedStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.stack.try_borrow() {
+            Ok(stack) => f.debug_list().entries(stack.iter()).finish(),
+            Err(_) => write!(f, "<borrowed>"),
+        }
+    }
+}
+
+/// Converts a stack into a shared stack with a single handle.
+impl<T> From<GenericStack<T>> for SharedStack<T> {
+    fn from(stack: GenericStack<T>) -> Self {
+        SharedStack {
+            stack: Rc::new(RefCell::new(stack)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::format;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn shared() {
+        let stack = SharedStack::new();
+        let handle = stack.clone();
+        assert!(stack.ptr_eq(&handle));
+        assert!(!stack.ptr_eq(&SharedStack::new()));
+
+        stack.push(1);
+        handle.push(2);
+        assert_eq!(stack.peek().as_deref(), Some(&2));
+        if let Some(mut top) = handle.peek_mut() {
+            *top = 20;
+        }
+        assert_eq!(format!("{stack:?}"), "[20, 1]");
+        assert_eq!(
+            handle.with(|stack| stack.iter().copied().collect::<Vec<_>>()),
+            vec![20, 1]
+        );
+        handle.with_mut(|stack| stack.push_bottom(0));
+
+        let snapshot = stack.snapshot();
+        stack.clear();
+        assert!(handle.is_empty());
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(SharedStack::from(snapshot).pop(), Some(20));
+    }
+
+    #[test]
+    fn debug_while_borrowed() {
+        let stack = SharedStack::from(GenericStack::from(vec![1]));
+        let top = stack.peek_mut();
+        assert_eq!(format!("{stack:?}"), "<borrowed>");
+        drop(top);
+        assert_eq!(format!("{stack:?}"), "[1]");
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_while_borrowed() {
+        let stack = SharedStack::from(GenericStack::from(vec![1]));
+        let _top = stack.peek();
+        stack.push(2);
+    }
+}
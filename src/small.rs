@@ -0,0 +1,303 @@
+//! A stack which stores its first elements inline.
+
+use crate::display::fmt_head_list;
+use crate::{ArrayStack, GenericStack};
+#[cfg(feature = "std")]
+use crate::{CapacityStack, Stack, StackExt};
+use core::fmt::{self, Debug, Display};
+
+/// `SmallStack<T, N>` stores its bottom `N` elements (4 by default) inline
+/// in an [`ArrayStack`] and only spills the elements above them to the
+/// heap nodes of a [`GenericStack`]. A stack that never grows beyond `N`
+/// elements does not allocate at all, like a `SmallVec`. For `N = 0`, all
+/// elements are stored in heap nodes.
+///
+/// Popping a spilled element keeps its node in the node pool of the
+/// `GenericStack`, i.e., a stack oscillating around `N` elements only
+/// allocates once.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::SmallStack;
+/// let mut stack: SmallStack<u32, 2> = SmallStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert!(!stack.spilled());
+/// stack.push(3);
+/// assert!(stack.spilled());
+/// assert_eq!(stack.pop(), Some(3));
+/// assert_eq!(stack.iter().collect::<Vec<_>>(), vec![&2, &1]);
+/// ```
+pub struct SmallStack<T, const N: usize = 4> {
+    inline: ArrayStack<T, N>,
+    /// the elements above the inline elements: only non-empty if the
+    /// inline elements are full
+    spilled: GenericStack<T>,
+}
+
+impl<T, const N: usize> SmallStack<T, N> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        SmallStack {
+            inline: ArrayStack::new(),
+            spilled: GenericStack::new(),
+        }
+    }
+
+    /// Create a new, empty stack which can store at least `capacity`
+    /// elements without allocating.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut stack = SmallStack::new();
+        stack.reserve(capacity);
+        stack
+    }
+
+    /// push a new element on the top element of the stack. The element is
+    /// stored inline if less than `N` elements are on the stack.
+    pub fn push(&mut self, element: T) {
+        if let Err(element) = self.inline.try_push(element) {
+            self.spilled.push(element);
+        }
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.spilled.pop().or_else(|| self.inline.pop())
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.spilled.peek().or_else(|| self.inline.peek())
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match self.spilled.peek_mut() {
+            Some(top) => Some(top),
+            None => self.inline.peek_mut(),
+        }
+    }
+
+    /// iterator from the top to the bottom of the stack
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.spilled.iter().chain(self.inline.iter())
+    }
+
+    /// mutable iterator from the top to the bottom of the stack
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        self.spilled.iter_mut().chain(self.inline.iter_mut())
+    }
+
+    /// Returns the number of elements stored on the stack.
+    pub fn len(&self) -> usize {
+        self.inline.len() + self.spilled.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the stack stores elements in heap nodes, i.e., if
+    /// it contains more than `N` elements.
+    pub fn spilled(&self) -> bool {
+        !self.spilled.is_empty()
+    }
+
+    /// Returns the number of elements the stack can store without
+    /// allocating: `N` plus the heap nodes, including the node pool.
+    pub fn capacity(&self) -> usize {
+        N + self.spilled.capacity()
+    }
+
+    /// Makes sure that at least `additional` more elements can be pushed
+    /// without allocating: the free inline slots are used first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::SmallStack;
+    /// let mut stack: SmallStack<u8, 4> = SmallStack::new();
+    /// stack.push(1);
+    /// stack.reserve(5);
+    /// assert_eq!(stack.capacity(), 6);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let free_inline = N - self.inline.len();
+        self.spilled.reserve(additional.saturating_sub(free_inline));
+    }
+
+    /// Removes all elements from the stack. The heap nodes are kept in
+    /// the node pool.
+    pub fn clear(&mut self) {
+        self.spilled.clear();
+        self.inline.clear();
+    }
+}
+
+impl<T, const N: usize> Default for SmallStack<T, N> {
+    fn default() -> Self {
+        SmallStack::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallStack<T, N> {
+    fn clone(&self) -> Self {
+        SmallStack {
+            inline: self.inline.clone(),
+            spilled: self.spilled.clone(),
+        }
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for SmallStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SmallStack<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallStack<T, N> {}
+
+/// [`SmallStack`] prints its elements in the same format as
+/// [`GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Display, const N: usize> Display for SmallStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self.iter())
+    }
+}
+
+/// Creates a stack from an iterator: the last item ends up on the top.
+impl<T, const N: usize> FromIterator<T> for SmallStack<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = SmallStack::new();
+        for element in iter {
+            stack.push(element);
+        }
+        stack
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> Stack<T> for SmallStack<T, N> {
+    fn new() -> Self {
+        SmallStack::new()
+    }
+
+    fn push(&mut self, element: T) {
+        SmallStack::push(self, element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        SmallStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        SmallStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        SmallStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> StackExt<T> for SmallStack<T, N> {
+    fn len(&self) -> usize {
+        SmallStack::len(self)
+    }
+}
+
+/// The capacity of a [`SmallStack`] includes the `N` inline slots.
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, const N: usize> CapacityStack<T> for SmallStack<T, N> {
+    fn with_capacity(capacity: usize) -> Self {
+        SmallStack::with_capacity(capacity)
+    }
+
+    fn capacity(&self) -> usize {
+        SmallStack::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        SmallStack::reserve(self, additional);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_inline_elements() {
+        let mut stack: SmallStack<u32, 0> = SmallStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.capacity(), 0);
+        stack.push(1);
+        assert!(!stack.is_empty());
+        assert!(stack.spilled());
+        assert_eq!(stack.len(), 1);
+        stack.push(2);
+        assert_eq!(stack.to_string(), "head->2->1.");
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        // the nodes are kept in the pool
+        assert_eq!(stack.capacity(), 2);
+        assert_eq!(SmallStack::<u32, 0>::with_capacity(3).capacity(), 3);
+    }
+
+    #[test]
+    fn reserve_across_the_inline_boundary() {
+        let mut stack: SmallStack<u8, 4> = SmallStack::with_capacity(2);
+        assert_eq!(stack.capacity(), 4);
+        stack.push(0);
+        stack.push(1);
+        // the free inline slots suffice
+        stack.reserve(2);
+        assert_eq!(stack.capacity(), 4);
+        // two inline slots and three heap nodes
+        stack.reserve(5);
+        assert_eq!(stack.capacity(), 7);
+        for i in 2..7 {
+            stack.push(i);
+        }
+        assert!(stack.spilled());
+        assert_eq!(stack.capacity(), 7);
+        stack.clear();
+        assert!(stack.is_empty());
+        stack.reserve(7);
+        assert_eq!(stack.capacity(), 7);
+    }
+
+    #[test]
+    fn clone_spilled() {
+        let mut stack: SmallStack<String, 2> = (0..5).map(|i| i.to_string()).collect();
+        assert!(stack.spilled());
+        let mut clone = stack.clone();
+        assert!(clone.spilled());
+        assert_eq!(clone, stack);
+        assert_eq!(clone.pop().as_deref(), Some("4"));
+        clone.push("x".to_string());
+        assert_eq!(stack.pop().as_deref(), Some("4"));
+        assert_eq!(
+            clone.iter().collect::<Vec<_>>(),
+            vec!["x", "3", "2", "1", "0"]
+        );
+        while stack.len() > 2 {
+            stack.pop();
+        }
+        assert!(!stack.spilled());
+        assert!(clone.spilled());
+        assert_eq!(stack.clone(), stack);
+    }
+}
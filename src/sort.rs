@@ -0,0 +1,281 @@
+//! Sorting a [`GenericStack<T>`] by relinking its nodes.
+//!
+//! A stack is sorted if its elements are in ascending order from the bottom
+//! to the top, i.e., `Vec::from(stack)` is sorted and `pop` returns the
+//! largest element first. The prev links of the nodes point up the stack:
+//! starting at the tail, they form a singly linked chain in exactly this
+//! order. We sort (and merge) such chains and then restore the next links
+//! in a single pass.
+
+use crate::{Allocator, GenericStack, Global, Link};
+use core::{mem, ptr};
+
+impl<T: Ord, A: Allocator> GenericStack<T, A> {
+    /// Sorts the stack in ascending order from the bottom to the top, i.e.,
+    /// the largest element ends up on the top of the stack. The sort is
+    /// stable: equal elements keep their relative order.
+    ///
+    /// This is a bottom-up merge sort over the links of the nodes. It runs
+    /// in O(n log n) time, and neither moves, clones, nor allocates
+    /// elements.
+    ///
+    /// If the comparison of two elements panics, the remaining elements of
+    /// the stack are leaked, and the stack is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+    /// stack.sort();
+    /// assert_eq!(Vec::from(stack), vec![1, 1, 2, 3, 4, 5, 6, 9]);
+    /// ```
+    pub fn sort(&mut self) {
+        // the stack is empty while the nodes are sorted: if the comparison
+        // panics, the nodes are leaked instead of being dropped twice
+        let mut chain = self.tail.take();
+        self.head = None;
+        let len = mem::take(&mut self.len);
+
+        // bin `i` is either empty or holds a sorted chain of `2^i` nodes
+        // that precede the nodes of the bins below it
+        let mut bins: [Link<T>; usize::BITS as usize] = [None; usize::BITS as usize];
+        while let Some(node) = chain {
+            // SAFETY: the node is owned by this stack, and we detach it
+            // from the remaining chain
+            let mut carry = unsafe {
+                chain = (*node.as_ptr()).prev;
+                (*node.as_ptr()).prev = None;
+                Some(node)
+            };
+            for bin in &mut bins {
+                let Some(sorted) = bin.take() else {
+                    *bin = carry;
+                    break;
+                };
+                // SAFETY: both chains are sorted chains of this stack
+                carry = unsafe { merge_chains(Some(sorted), carry) };
+            }
+        }
+
+        let mut sorted = None;
+        for bin in bins {
+            // SAFETY: both chains are sorted chains of this stack
+            sorted = unsafe { merge_chains(bin, sorted) };
+        }
+        // SAFETY: the chain contains all nodes of this stack
+        unsafe { self.relink_chain(sorted, len) };
+        trace!("sort: length {}", self.len);
+    }
+}
+
+impl<T: Ord> GenericStack<T, Global> {
+    /// Merges the sorted stack `other` into this sorted stack, such that the
+    /// result is sorted again. The nodes of both stacks are relinked in
+    /// linear time, and for equal elements, the elements of this stack end
+    /// up below the elements of `other`. If one of the stacks is not sorted,
+    /// the order of the result is unspecified, but no element is lost.
+    ///
+    /// Like [`GenericStack::append`], this is only available for stacks
+    /// using the [`Global`] allocator. If the comparison of two elements
+    /// panics, the elements of both stacks are leaked, and this stack is
+    /// empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::from(vec![1, 4, 6]);
+    /// stack.merge(GenericStack::from(vec![2, 3, 7]));
+    /// assert_eq!(Vec::from(stack), vec![1, 2, 3, 4, 6, 7]);
+    /// ```
+    pub fn merge(&mut self, mut other: GenericStack<T>) {
+        #[cfg(feature = "stats")]
+        self.record_moved(&mut other.stats, other.len);
+        let len = mem::take(&mut self.len) + mem::take(&mut other.len);
+        let chain = self.tail.take();
+        let other_chain = other.tail.take();
+        self.head = None;
+        other.head = None;
+        // SAFETY: both chains are linked with the prev links from the
+        // bottom to the top, and all nodes are now owned by this stack
+        unsafe {
+            let merged = merge_chains(chain, other_chain);
+            self.relink_chain(merged, len);
+        }
+        #[cfg(feature = "stats")]
+        self.record_depth();
+        trace!("merge: length {}", self.len);
+    }
+}
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// links a chain of nodes, which is linked from the bottom to the top
+    /// with the prev links, as the new contents of the stack: the next
+    /// links are restored, and the head, the tail, and the length are
+    /// updated.
+    ///
+    /// # Safety
+    ///
+    /// The chain must consist of exactly `len` nodes allocated by this
+    /// stack, and the stack must not contain any other nodes.
+    unsafe fn relink_chain(&mut self, bottom: Link<T>, len: usize) {
+        let mut below = None;
+        let mut link = bottom;
+        while let Some(node) = link {
+            (*node.as_ptr()).next = below;
+            below = Some(node);
+            link = (*node.as_ptr()).prev;
+        }
+        self.head = below;
+        self.tail = bottom;
+        self.len = len;
+    }
+}
+
+/// merges two sorted chains, which are linked with the prev links, into
+/// one sorted chain and returns its first node. For equal elements, the
+/// nodes of `a` precede the nodes of `b`.
+///
+/// # Safety
+///
+/// Both chains must be valid, disjoint, and terminated by `None`.
+unsafe fn merge_chains<T: Ord>(mut a: Link<T>, mut b: Link<T>) -> Link<T> {
+    let mut first = None;
+    let mut last: *mut Link<T> = ptr::addr_of_mut!(first);
+    loop {
+        match (a, b) {
+            (Some(x), Some(y)) => {
+                let node = if (*y.as_ptr()).element < (*x.as_ptr()).element {
+                    b = (*y.as_ptr()).prev;
+                    y
+                } else {
+                    a = (*x.as_ptr()).prev;
+                    x
+                };
+                *last = Some(node);
+                last = ptr::addr_of_mut!((*node.as_ptr()).prev);
+            }
+            (rest, None) | (None, rest) => {
+                *last = rest;
+                return first;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn sort() {
+        let mut stack: GenericStack<u8> = GenericStack::new();
+        stack.sort();
+        assert!(stack.is_empty());
+
+        let mut stack = GenericStack::from(vec![5, 2, 8, 1, 9, 3, 7]);
+        stack.sort();
+        assert_eq!(stack.len(), 7);
+        assert_eq!(stack.peek(), Some(&9));
+        assert!(stack.iter().rev().eq([1, 2, 3, 5, 7, 8, 9].iter()));
+        // the stack is still usable
+        stack.push(0);
+        assert_eq!(stack.pop(), Some(0));
+        assert_eq!(Vec::from(stack), vec![1, 2, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut stack = GenericStack::from(vec![1, 3, 5, 5]);
+        stack.merge(GenericStack::new());
+        assert_eq!(stack.len(), 4);
+        stack.merge(GenericStack::from(vec![0, 5, 6, 7]));
+        assert_eq!(stack.len(), 8);
+        assert_eq!(stack.peek(), Some(&7));
+        assert_eq!(Vec::from(stack), vec![0, 1, 3, 5, 5, 5, 6, 7]);
+
+        let mut stack = GenericStack::new();
+        stack.merge(GenericStack::from(vec![1, 2]));
+        stack.push(3);
+        assert!(stack.iter().eq([3, 2, 1].iter()));
+    }
+
+    /// a key that is only compared by its first field, the second field
+    /// tells the original position of an element
+    #[derive(Debug, Clone, Copy)]
+    struct Key(u32, usize);
+
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Key {}
+
+    impl PartialOrd for Key {
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Key {
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn merge_is_stable() {
+        let mut stack = GenericStack::from(vec![Key(1, 0), Key(2, 1)]);
+        stack.merge(GenericStack::from(vec![Key(1, 2), Key(2, 3)]));
+        let merged = Vec::from(stack);
+        assert!(merged.iter().map(|k| k.1).eq([0, 2, 1, 3]));
+    }
+
+    #[test]
+    fn sort_is_stable() {
+        let n = if cfg!(miri) { 50 } else { 1000 };
+        let keys: Vec<_> = (0..n).map(|i| Key((i * 7919 % 13) as u32, i)).collect();
+        let mut expected = keys.clone();
+        expected.sort();
+        let mut stack = GenericStack::from(keys);
+        stack.sort();
+        let sorted = Vec::from(stack);
+        assert!(sorted.iter().map(|k| k.1).eq(expected.iter().map(|k| k.1)));
+    }
+
+    #[test]
+    // Miri rightly reports the leaked elements
+    #[cfg_attr(miri, ignore)]
+    fn sort_leaks_on_panic() {
+        struct Panicking(u8);
+        impl PartialEq for Panicking {
+            fn eq(&self, _: &Self) -> bool {
+                panic!("eq")
+            }
+        }
+        impl Eq for Panicking {}
+        impl PartialOrd for Panicking {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Panicking {
+            fn cmp(&self, _: &Self) -> core::cmp::Ordering {
+                panic!("cmp")
+            }
+        }
+
+        let mut stack = GenericStack::new();
+        stack.push(Panicking(1));
+        stack.push(Panicking(2));
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| stack.sort()));
+        assert!(result.is_err());
+        assert!(stack.is_empty());
+        stack.push(Panicking(3));
+        assert_eq!(stack.pop().map(|p| p.0), Some(3));
+    }
+}
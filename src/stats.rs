@@ -0,0 +1,129 @@
+//! Operation counters of [`GenericStack`] (requires feature `stats`).
+
+use crate::{Allocator, GenericStack};
+
+/// The statistics of a [`GenericStack`] returned by
+/// [`GenericStack::stats`], e.g., to choose the stack size of an
+/// interpreter from the high-water mark of a typical run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Stats {
+    /// the number of elements added to the stack, e.g., with `push`,
+    /// `push_bottom`, `insert`, `extend`, or `append`
+    pub pushes: u64,
+    /// the number of elements removed from the stack, e.g., with `pop`,
+    /// `pop_n`, `remove`, `retain`, `clear`, or the consuming iterator
+    pub pops: u64,
+    /// the maximum length the stack ever reached, independent of the
+    /// operation that added the elements
+    pub high_water_mark: usize,
+}
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Returns the number of pushes and pops since the stack was created
+    /// or the statistics were reset, and the maximum length the stack
+    /// reached in the meantime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::GenericStack;
+    /// let mut stack = GenericStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// stack.pop();
+    /// stack.push(3);
+    /// let stats = stack.stats();
+    /// assert_eq!((stats.pushes, stats.pops), (3, 1));
+    /// assert_eq!(stats.high_water_mark, 2);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// resets the counters to 0 and the high-water mark to the current
+    /// length of the stack.
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats {
+            high_water_mark: self.len,
+            ..Stats::default()
+        };
+    }
+
+    /// updates the high-water mark after the length has increased
+    pub(crate) fn record_depth(&mut self) {
+        self.stats.high_water_mark = self.stats.high_water_mark.max(self.len);
+    }
+
+    /// counts `count` elements which were relinked from another stack to
+    /// this stack as pushes of this stack and as pops of the other stack,
+    /// whose statistics are `from`
+    pub(crate) fn record_moved(&mut self, from: &mut Stats, count: usize) {
+        self.stats.pushes += count as u64;
+        from.pops += count as u64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// every element that is added is counted as a push, and every element
+    /// that is removed as a pop
+    fn assert_balanced<T, A: Allocator>(stack: &GenericStack<T, A>) {
+        let stats = stack.stats();
+        assert_eq!(stats.pushes - stats.pops, stack.len() as u64);
+    }
+
+    #[test]
+    fn stats() {
+        let mut stack = GenericStack::new();
+        assert_eq!(stack.stats(), Stats::default());
+        stack.extend(0..10);
+        for _ in 0..4 {
+            stack.pop();
+        }
+        stack.push_bottom(-1);
+        stack.insert(2, -2);
+        let stats = stack.stats();
+        assert_eq!((stats.pushes, stats.pops), (12, 4));
+        assert_eq!(stats.high_water_mark, 10);
+        assert_balanced(&stack);
+
+        assert_eq!(stack.pop_n(2).len(), 2);
+        assert_eq!(stack.remove(1), Some(3));
+        stack.retain(|v| *v != 0);
+        assert_balanced(&stack);
+
+        let mut other = GenericStack::from(vec![1, 2, 3]);
+        stack.append(&mut other);
+        assert_balanced(&stack);
+        assert_balanced(&other);
+        assert_eq!(stack.stats().high_water_mark, 10);
+
+        let (even, odd) = (0..7)
+            .collect::<GenericStack<_>>()
+            .partition(|v| v % 2 == 0);
+        assert_balanced(&even);
+        assert_balanced(&odd);
+        assert_eq!(odd.stats().high_water_mark, 3);
+
+        let mut stack: GenericStack<_> = (0..5).collect();
+        let split = stack.cursor_mut().split_after();
+        assert_balanced(&stack);
+        assert_balanced(&split);
+        stack.clear();
+        assert_balanced(&stack);
+    }
+
+    #[test]
+    fn reset_stats() {
+        let mut stack: GenericStack<_> = (0..8).collect();
+        stack.reset_stats();
+        assert_eq!(stack.stats().high_water_mark, 8);
+        stack.append(&mut GenericStack::from(vec![1, 2, 3]));
+        stack.clear();
+        let stats = stack.stats();
+        assert_eq!((stats.pushes, stats.pops), (3, 11));
+        assert_eq!(stats.high_water_mark, 11);
+    }
+}
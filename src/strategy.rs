@@ -0,0 +1,243 @@
+//! Optional support for [`proptest`]: enable feature `proptest` to generate
+//! stacks in property tests.
+//!
+//! [`any_stack`] generates stacks of arbitrary elements, and [`stack_of`]
+//! generates stacks of elements of a given strategy. A failing stack is
+//! shrunk like a stack is used: we first pop elements from the top of the
+//! stack and then shrink the remaining elements, starting at the top.
+//!
+//! # Example
+//!
+//! ```
+//! use ll_stack::strategy::any_stack;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn reverse_twice(mut stack in any_stack::<u8>()) {
+//!         let original = stack.clone();
+//!         stack.reverse();
+//!         stack.reverse();
+//!         prop_assert_eq!(stack, original);
+//!     }
+//! }
+//! # reverse_twice();
+//! ```
+
+use crate::GenericStack;
+use alloc::vec::Vec;
+use proptest::arbitrary::{any, any_with, Arbitrary};
+use proptest::collection::SizeRange;
+use proptest::strategy::{NewTree, Strategy, ValueTree};
+use proptest::test_runner::TestRunner;
+
+/// Returns a strategy generating stacks of arbitrary elements with up to
+/// 99 elements.
+pub fn any_stack<T: Arbitrary>() -> StackStrategy<T::Strategy> {
+    stack_of(any::<T>(), SizeRange::default())
+}
+
+/// Returns a strategy generating stacks whose elements are generated by
+/// `element` and whose length is in `size`.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::strategy::stack_of;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     fn small_stacks(stack in stack_of(0..10u8, 1..5)) {
+///         prop_assert!((1..5).contains(&stack.len()));
+///         prop_assert!(stack.iter().all(|v| *v < 10));
+///     }
+/// }
+/// # small_stacks();
+/// ```
+pub fn stack_of<S: Strategy>(element: S, size: impl Into<SizeRange>) -> StackStrategy<S> {
+    StackStrategy {
+        element,
+        size: size.into(),
+    }
+}
+
+/// Strategy returned by [`any_stack`] and [`stack_of`].
+#[derive(Debug, Clone)]
+pub struct StackStrategy<S> {
+    element: S,
+    size: SizeRange,
+}
+
+impl<S: Strategy> Strategy for StackStrategy<S> {
+    type Tree = StackValueTree<S::Tree>;
+    type Value = GenericStack<S::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let len = (self.size.start()..=self.size.end_incl())
+            .new_tree(runner)?
+            .current();
+        // the elements are stored from the bottom to the top
+        let elements = (0..len)
+            .map(|_| self.element.new_tree(runner))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(StackValueTree {
+            elements,
+            len,
+            min_len: self.size.start(),
+            popping: true,
+            shrink: 0,
+            last: None,
+        })
+    }
+}
+
+/// the last successful step of [`StackValueTree::simplify`]
+#[derive(Debug, Clone, Copy)]
+enum Shrink {
+    /// popped the top element
+    Pop,
+    /// shrunk the element at the given index (counted from the bottom)
+    Element(usize),
+}
+
+/// Value tree of [`StackStrategy`]: it shrinks a stack by popping elements
+/// from the top, and then by shrinking the remaining elements from the top
+/// to the bottom.
+#[derive(Debug, Clone)]
+pub struct StackValueTree<E> {
+    /// the value trees of the elements from the bottom to the top
+    elements: Vec<E>,
+    /// the number of elements of the current stack
+    len: usize,
+    min_len: usize,
+    /// `true` while we try to pop elements
+    popping: bool,
+    /// the number of elements (from the top) that cannot be shrunk anymore
+    shrink: usize,
+    last: Option<Shrink>,
+}
+
+impl<E: ValueTree> ValueTree for StackValueTree<E> {
+    type Value = GenericStack<E::Value>;
+
+    fn current(&self) -> Self::Value {
+        self.elements[..self.len]
+            .iter()
+            .map(ValueTree::current)
+            .collect()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.popping && self.len > self.min_len {
+            self.len -= 1;
+            self.last = Some(Shrink::Pop);
+            return true;
+        }
+        self.popping = false;
+
+        while self.shrink < self.len {
+            let index = self.len - 1 - self.shrink;
+            if self.elements[index].simplify() {
+                self.last = Some(Shrink::Element(index));
+                return true;
+            }
+            self.shrink += 1;
+        }
+        self.last = None;
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.last {
+            None => false,
+            Some(Shrink::Pop) => {
+                // the popped element is required: we stop popping and
+                // shrink the elements instead
+                self.len += 1;
+                self.popping = false;
+                self.last = None;
+                true
+            }
+            Some(Shrink::Element(index)) => {
+                if self.elements[index].complicate() {
+                    true
+                } else {
+                    self.last = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// With feature `proptest`, `any::<GenericStack<T>>()` is the same as
+/// [`any_stack::<T>()`](any_stack).
+impl<T: Arbitrary> Arbitrary for GenericStack<T> {
+    type Parameters = (SizeRange, T::Parameters);
+    type Strategy = StackStrategy<T::Strategy>;
+
+    fn arbitrary_with((size, parameters): Self::Parameters) -> Self::Strategy {
+        stack_of(any_with::<T>(parameters), size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn shrink_pops_from_the_top() {
+        let mut runner = TestRunner::deterministic();
+        let mut tree = stack_of(0..100u32, 5..10).new_tree(&mut runner).unwrap();
+        let original = tree.current();
+
+        // as long as the smaller stack "fails", we keep popping
+        let mut len = original.len();
+        while len > 5 {
+            assert!(tree.simplify());
+            len -= 1;
+            let current = tree.current();
+            assert_eq!(current.len(), len);
+            // the remaining elements are the bottom of the original stack
+            assert!(current.iter().rev().eq(original.iter().rev().take(len)));
+        }
+    }
+
+    #[test]
+    fn complicate_pop() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = stack_of(1..100u32, 1..10);
+        let mut tree = strategy.new_tree(&mut runner).unwrap();
+        while tree.current().len() < 2 {
+            tree = strategy.new_tree(&mut runner).unwrap();
+        }
+        let original = tree.current();
+        assert!(tree.simplify());
+        assert!(tree.complicate());
+        assert_eq!(tree.current(), original);
+
+        // the popped element was required: we shrink the elements instead
+        while tree.simplify() {
+            assert_eq!(tree.current().len(), original.len());
+        }
+    }
+
+    #[test]
+    fn shrink_elements() {
+        let mut runner = TestRunner::deterministic();
+        let mut tree = stack_of(0..100u32, 3).new_tree(&mut runner).unwrap();
+        // the length cannot be shrunk, i.e., only the elements are shrunk
+        while tree.simplify() {
+            assert_eq!(tree.current().len(), 3);
+        }
+        assert_eq!(Vec::from(tree.current()), vec![0, 0, 0]);
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary(stack in any::<GenericStack<u8>>()) {
+            prop_assert!(stack.len() < 100);
+            prop_assert_eq!(GenericStack::from(Vec::from(stack.clone())), stack);
+        }
+    }
+}
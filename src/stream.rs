@@ -0,0 +1,187 @@
+//! Adapters which turn stacks into a [`Stream`] of crate `futures-core`.
+
+#[cfg(feature = "async")]
+use crate::AsyncStack;
+use crate::{Allocator, GenericStack, Global, IntoIter};
+#[cfg(feature = "async")]
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::stream::{FusedStream, Stream};
+
+impl<T, A: Allocator> GenericStack<T, A> {
+    /// Converts the stack into a [`Stream`] which yields the elements from
+    /// the top to the bottom, e.g., to process them with the combinators of
+    /// `StreamExt`. The stream is never pending.
+    pub fn into_stream(self) -> IntoStream<T, A> {
+        IntoStream {
+            iter: self.into_iter(),
+        }
+    }
+}
+
+/// The stream returned by [`GenericStack::into_stream`].
+pub struct IntoStream<T, A: Allocator = Global> {
+    iter: IntoIter<T, A>,
+}
+
+// the stream never pins the stack or the allocator
+impl<T, A: Allocator> Unpin for IntoStream<T, A> {}
+
+impl<T, A: Allocator> Stream for IntoStream<T, A> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Poll::Ready(self.get_mut().iter.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator> FusedStream for IntoStream<T, A> {
+    fn is_terminated(&self) -> bool {
+        self.iter.len() == 0
+    }
+}
+
+impl<T, A: Allocator> Debug for IntoStream<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IntoStream")
+            .field("len", &self.iter.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncStack<T> {
+    /// Converts a shared stack into an endless [`Stream`] which pops the
+    /// top element whenever it is polled and waits for the next push if
+    /// the stack is empty. Other handles of the stack can still push and
+    /// pop elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ll_stack::AsyncStack;
+    /// use futures_core::Stream;
+    /// use std::pin::pin;
+    /// use std::sync::Arc;
+    /// use std::task::{Context, Poll, Waker};
+    ///
+    /// let stack = Arc::new(AsyncStack::new());
+    /// let mut stream = pin!(Arc::clone(&stack).into_stream());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+    /// stack.push(1);
+    /// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+    /// ```
+    pub fn into_stream(self: Arc<Self>) -> PopStream<T> {
+        PopStream {
+            stack: self,
+            id: None,
+        }
+    }
+}
+
+/// The stream returned by [`AsyncStack::into_stream`].
+#[cfg(feature = "async")]
+pub struct PopStream<T> {
+    stack: Arc<AsyncStack<T>>,
+    /// identifies the waker of the pending pop like [`crate::Pop`]
+    id: Option<usize>,
+}
+
+#[cfg(feature = "async")]
+impl<T> Stream for PopStream<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        this.stack.poll_pop(&mut this.id, cx).map(Some)
+    }
+}
+
+/// The stream never ends.
+#[cfg(feature = "async")]
+impl<T> FusedStream for PopStream<T> {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
+
+/// Dropping a stream which was woken up, but did not pop an element,
+/// passes the wakeup on to the next waiting task.
+#[cfg(feature = "async")]
+impl<T> Drop for PopStream<T> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.stack.cancel_pop(id);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Debug for PopStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PopStream")
+            .field("stack", &self.stack)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::task::Waker;
+
+    fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(item)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn into_stream() {
+        let stream = GenericStack::from(vec![1, 2, 3]).into_stream();
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+        assert!(!stream.is_terminated());
+        assert_eq!(collect(stream), vec![3, 2, 1]);
+
+        let mut stream = GenericStack::<i32>::new().into_stream();
+        assert!(stream.is_terminated());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn pop_stream() {
+        let stack = Arc::new(AsyncStack::from(GenericStack::from(vec![1, 2])));
+        let mut stream = Arc::clone(&stack).into_stream();
+        assert!(!stream.is_terminated());
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(2))
+        );
+        assert_eq!(stack.try_pop(), Some(1));
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+
+        // the pending stream is woken up by a push
+        stack.push(3);
+        assert_eq!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(3))
+        );
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+        drop(stream);
+        assert_eq!(format!("{stack:?}"), "AsyncStack { len: 0, waiters: 0 }");
+    }
+}
@@ -0,0 +1,315 @@
+//! A stack which maintains a running aggregate of its elements.
+//!
+//! [`TrackedStack<T, A>`] generalizes [`crate::MinStack`]: the aggregate
+//! is defined by a type implementing [`Aggregate<T>`]. This module provides
+//! the aggregates [`Min`], [`Max`], and [`Sum`].
+
+use crate::display::fmt_head_list;
+use crate::GenericStack;
+#[cfg(feature = "std")]
+use crate::{Stack, StackExt};
+use core::fmt::{self, Debug, Display};
+use core::marker::PhantomData;
+use core::ops::Add;
+
+/// An `Aggregate<T>` defines how to summarize the elements of a
+/// [`TrackedStack`]. Like a semigroup, `combine` must be associative. It
+/// does not need to be commutative: `combine` is always called with the
+/// aggregate of the older (lower) elements as first argument.
+pub trait Aggregate<T> {
+    /// the type of the aggregated value
+    type Output;
+
+    /// Returns the aggregate of a single element.
+    fn lift(element: &T) -> Self::Output;
+
+    /// Combines the aggregate `below` of older elements with the aggregate
+    /// `above` of newer elements.
+    fn combine(below: &Self::Output, above: &Self::Output) -> Self::Output;
+}
+
+/// Aggregates the minimal element.
+pub struct Min;
+
+impl<T: Ord + Clone> Aggregate<T> for Min {
+    type Output = T;
+
+    fn lift(element: &T) -> T {
+        element.clone()
+    }
+
+    fn combine(below: &T, above: &T) -> T {
+        below.min(above).clone()
+    }
+}
+
+/// Aggregates the maximal element.
+pub struct Max;
+
+impl<T: Ord + Clone> Aggregate<T> for Max {
+    type Output = T;
+
+    fn lift(element: &T) -> T {
+        element.clone()
+    }
+
+    fn combine(below: &T, above: &T) -> T {
+        below.max(above).clone()
+    }
+}
+
+/// Aggregates the sum of all elements.
+pub struct Sum;
+
+impl<T: Clone + Add<Output = T>> Aggregate<T> for Sum {
+    type Output = T;
+
+    fn lift(element: &T) -> T {
+        element.clone()
+    }
+
+    fn combine(below: &T, above: &T) -> T {
+        below.clone() + above.clone()
+    }
+}
+
+/// `TrackedStack<T, A>` is a stack that maintains the aggregate `A` of its
+/// elements incrementally: [`TrackedStack::aggregate`] is O(1) (assuming
+/// that [`Aggregate::combine`] is O(1)).
+///
+/// Each node stores, next to its element, the aggregate of all elements
+/// below it. The aggregate of the stack combines this stored aggregate with
+/// the top element. Since the stored aggregate does not depend on the top
+/// element, the top element can still be modified with
+/// [`TrackedStack::peek_mut`].
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::tracked::{Max, Sum};
+/// use ll_stack::TrackedStack;
+/// let mut sums: TrackedStack<u32, Sum> = TrackedStack::new();
+/// let mut maxima: TrackedStack<u32, Max> = TrackedStack::new();
+/// for v in [3, 9, 4] {
+///     sums.push(v);
+///     maxima.push(v);
+/// }
+/// assert_eq!(sums.aggregate(), Some(16));
+/// assert_eq!(maxima.aggregate(), Some(9));
+/// maxima.pop();
+/// maxima.pop();
+/// assert_eq!(maxima.aggregate(), Some(3));
+/// ```
+pub struct TrackedStack<T, A: Aggregate<T>> {
+    stack: GenericStack<Entry<T, A::Output>>,
+    aggregate: PhantomData<fn() -> A>,
+}
+
+struct Entry<T, O> {
+    element: T,
+    /// the aggregate of all elements below this entry, `None` for the bottom entry
+    below: Option<O>,
+}
+
+impl<T, A: Aggregate<T>> TrackedStack<T, A> {
+    /// Create a new, empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        TrackedStack {
+            stack: GenericStack::new(),
+            aggregate: PhantomData,
+        }
+    }
+
+    /// push a new element on the top element of the stack.
+    pub fn push(&mut self, element: T) {
+        let below = self.aggregate();
+        self.stack.push(Entry { element, below });
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop().map(|entry| entry.element)
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.stack.peek().map(|top| &top.element)
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack
+    /// is not empty. Modifying the top element updates the aggregate.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.stack.peek_mut().map(|top| &mut top.element)
+    }
+
+    /// Returns the aggregate of all elements or `None` if the stack is empty.
+    #[must_use]
+    pub fn aggregate(&self) -> Option<A::Output> {
+        self.stack.peek().map(|top| {
+            let lifted = A::lift(&top.element);
+            match &top.below {
+                Some(below) => A::combine(below, &lifted),
+                None => lifted,
+            }
+        })
+    }
+
+    /// iterator from the top to the bottom of the stack
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.stack.iter().map(|entry| &entry.element)
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+impl<T, A: Aggregate<T>> Default for TrackedStack<T, A> {
+    fn default() -> Self {
+        TrackedStack::new()
+    }
+}
+
+/// Cloning pushes clones of the elements from the bottom to the top on a
+/// new stack, i.e., the aggregates are recomputed.
+impl<T: Clone, A: Aggregate<T>> Clone for TrackedStack<T, A> {
+    fn clone(&self) -> Self {
+        let mut stack = TrackedStack::new();
+        for element in self.iter().rev() {
+            stack.push(element.clone());
+        }
+        stack
+    }
+}
+
+impl<T: Debug, A: Aggregate<T>> Debug for TrackedStack<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, A: Aggregate<T>> PartialEq for TrackedStack<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, A: Aggregate<T>> Eq for TrackedStack<T, A> {}
+
+/// [`TrackedStack`] prints its elements in the same format as
+/// [`crate::GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Display, A: Aggregate<T>> Display for TrackedStack<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self.iter())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, A: Aggregate<T>> Stack<T> for TrackedStack<T, A> {
+    fn new() -> Self {
+        TrackedStack::new()
+    }
+
+    fn push(&mut self, element: T) {
+        TrackedStack::push(self, element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        TrackedStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        TrackedStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        TrackedStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display, A: Aggregate<T>> StackExt<T> for TrackedStack<T, A> {
+    fn len(&self) -> usize {
+        TrackedStack::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn min_max_sum() {
+        let mut min: TrackedStack<i32, Min> = TrackedStack::new();
+        let mut max: TrackedStack<i32, Max> = TrackedStack::new();
+        let mut sum: TrackedStack<i32, Sum> = TrackedStack::new();
+        assert_eq!(min.aggregate(), None);
+        assert_eq!(sum.aggregate(), None);
+
+        for v in [5, 2, 8, 3] {
+            min.push(v);
+            max.push(v);
+            sum.push(v);
+        }
+        assert_eq!(min.aggregate(), Some(2));
+        assert_eq!(max.aggregate(), Some(8));
+        assert_eq!(sum.aggregate(), Some(18));
+
+        if let Some(top) = sum.peek_mut() {
+            *top = 10;
+        }
+        assert_eq!(sum.aggregate(), Some(25));
+
+        for _ in 0..2 {
+            min.pop();
+            max.pop();
+            sum.pop();
+        }
+        assert_eq!(min.aggregate(), Some(2));
+        assert_eq!(max.aggregate(), Some(5));
+        assert_eq!(sum.aggregate(), Some(7));
+    }
+
+    /// A non-commutative aggregate: the concatenation from the bottom to the top.
+    struct Concat;
+
+    impl Aggregate<char> for Concat {
+        type Output = String;
+
+        fn lift(element: &char) -> String {
+            String::from(*element)
+        }
+
+        fn combine(below: &String, above: &String) -> String {
+            let mut result = below.clone();
+            result.push_str(above);
+            result
+        }
+    }
+
+    #[test]
+    fn custom_aggregate() {
+        let mut stack: TrackedStack<char, Concat> = TrackedStack::new();
+        for c in "abc".chars() {
+            stack.push(c);
+        }
+        assert_eq!(stack.aggregate().as_deref(), Some("abc"));
+        assert_eq!(stack.to_string(), "head->c->b->a.");
+
+        let mut copy = stack.clone();
+        assert_eq!(copy, stack);
+        copy.pop();
+        assert_eq!(copy.aggregate().as_deref(), Some("ab"));
+    }
+}
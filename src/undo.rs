@@ -0,0 +1,181 @@
+//! A stack of states or actions with undo and redo.
+
+use crate::{GenericStack, Iter};
+
+/// `UndoStack<T>` is the history of an editor-like application: it
+/// consists of two [`GenericStack<T>`]s, one with the elements that can be
+/// undone and one with the elements that can be redone.
+///
+/// [`UndoStack::undo`] moves the most recent element to the redo stack,
+/// [`UndoStack::redo`] moves it back, and [`UndoStack::push`] clears the
+/// redo stack since the undone elements are no longer a valid future.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::UndoStack;
+/// let mut history = UndoStack::new();
+/// history.push("insert a");
+/// history.push("insert b");
+/// assert_eq!(history.undo(), Some(&"insert b"));
+/// assert_eq!(history.peek(), Some(&"insert a"));
+/// assert_eq!(history.redo(), Some(&"insert b"));
+///
+/// history.undo();
+/// history.push("insert c");
+/// // "insert b" can no longer be redone
+/// assert_eq!(history.redo(), None);
+/// assert_eq!(history.peek(), Some(&"insert c"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoStack<T> {
+    /// the elements that can be undone, the most recent one on the top
+    done: GenericStack<T>,
+    /// the elements that can be redone, the most recently undone on the top
+    undone: GenericStack<T>,
+}
+
+impl<T> UndoStack<T> {
+    /// Create a new, empty history.
+    #[must_use]
+    pub fn new() -> Self {
+        UndoStack {
+            done: GenericStack::new(),
+            undone: GenericStack::new(),
+        }
+    }
+
+    /// pushes `element` as the most recent element and drops all elements
+    /// that could be redone.
+    pub fn push(&mut self, element: T) {
+        self.undone.clear();
+        self.done.push(element);
+    }
+
+    /// Moves the most recent element to the redo stack and borrows it, or
+    /// returns `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<&T> {
+        let element = self.done.pop()?;
+        self.undone.push(element);
+        self.undone.peek()
+    }
+
+    /// Moves the most recently undone element back and borrows it, or
+    /// returns `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<&T> {
+        let element = self.undone.pop()?;
+        self.done.push(element);
+        self.done.peek()
+    }
+
+    /// borrows the most recent element, i.e., the element that would be
+    /// undone next.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.done.peek()
+    }
+
+    /// borrows the element that would be redone next.
+    #[must_use]
+    pub fn peek_redo(&self) -> Option<&T> {
+        self.undone.peek()
+    }
+
+    /// Returns `true` if there is an element to undo.
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Returns `true` if there is an element to redo.
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// iterator over the elements that can be undone, from the most recent
+    /// to the oldest one.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.done.iter()
+    }
+
+    /// iterator over the elements that can be redone, from the most
+    /// recently undone one.
+    #[must_use]
+    pub fn iter_redo(&self) -> Iter<'_, T> {
+        self.undone.iter()
+    }
+
+    /// Returns the number of elements that can be undone.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    /// Returns `true` if there is nothing to undo.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.done.is_empty()
+    }
+
+    /// Removes all elements, including the elements that could be redone.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+impl<T> Default for UndoStack<T> {
+    fn default() -> Self {
+        UndoStack::new()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a UndoStack<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn undo_redo() {
+        let mut history = UndoStack::new();
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), None);
+        assert!(!history.can_undo());
+
+        for v in 1..=3 {
+            history.push(v);
+        }
+        assert_eq!(history.undo(), Some(&3));
+        assert_eq!(history.undo(), Some(&2));
+        assert_eq!(history.len(), 1);
+        assert!(history.can_redo());
+        assert_eq!(history.peek_redo(), Some(&2));
+        assert!(history.iter_redo().eq([2, 3].iter()));
+
+        assert_eq!(history.redo(), Some(&2));
+        assert_eq!(history.peek(), Some(&2));
+        assert!(history.iter().eq([2, 1].iter()));
+
+        // a new element clears the redo branch
+        history.push(4);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert!(history.iter().eq([4, 2, 1].iter()));
+
+        assert_eq!(history.undo(), Some(&4));
+        history.clear();
+        assert!(history.is_empty());
+        assert!(!history.can_redo());
+        assert_eq!(history, UndoStack::default());
+    }
+}
@@ -0,0 +1,269 @@
+//! A stack which stores its elements in a `Vec`.
+
+use crate::display::fmt_head_list;
+#[cfg(feature = "std")]
+use crate::{CapacityStack, Stack, StackExt};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display};
+use core::iter::Rev;
+use core::slice;
+
+/// `VecStack<T>` is a `Vec`-based implementation of a stack: the top of the
+/// stack is the last element of the vector. It implements the trait
+/// [`Stack`], i.e., code written against the trait can switch between
+/// [`crate::GenericStack`] and `VecStack`, e.g., to compare their
+/// performance. We also use it as reference implementation in our tests.
+///
+/// # Example
+///
+/// ```
+/// use ll_stack::VecStack;
+/// let mut stack = VecStack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.peek(), Some(&2));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VecStack<T> {
+    elements: Vec<T>,
+}
+
+impl<T> VecStack<T> {
+    /// Create a new, empty stack storing elements of type `<T>`.
+    #[must_use]
+    pub const fn new() -> Self {
+        VecStack {
+            elements: Vec::new(),
+        }
+    }
+
+    /// Create a new, empty stack which can store at least `capacity`
+    /// elements without reallocating.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        VecStack {
+            elements: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// push a new element on the top element of the stack.
+    pub fn push(&mut self, element: T) {
+        self.elements.push(element);
+    }
+
+    /// Removes and returns the top element of the stack if it exists.
+    pub fn pop(&mut self) -> Option<T> {
+        self.elements.pop()
+    }
+
+    /// borrows the top element of the stack if the stack is not empty.
+    #[must_use]
+    pub fn peek(&self) -> Option<&T> {
+        self.elements.last()
+    }
+
+    /// borrows the top element of the stack as a mutable value if the stack is not empty.
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.elements.last_mut()
+    }
+
+    /// iterator from the top to the bottom of the stack
+    pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+        self.elements.iter().rev()
+    }
+
+    /// mutable iterator from the top to the bottom of the stack
+    pub fn iter_mut(&mut self) -> Rev<slice::IterMut<'_, T>> {
+        self.elements.iter_mut().rev()
+    }
+
+    /// borrows the elements as a slice from the bottom to the top, i.e.,
+    /// the last element of the slice is the top of the stack.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        &self.elements
+    }
+
+    /// Returns the number of elements stored on the stack.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the stack does not contain any elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the number of elements the stack can store without
+    /// reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.elements.capacity()
+    }
+
+    /// reserves capacity for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        self.elements.reserve(additional);
+    }
+
+    /// Removes all elements from the stack.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+    }
+}
+
+impl<T> Default for VecStack<T> {
+    fn default() -> Self {
+        VecStack::new()
+    }
+}
+
+/// Converts a `Vec<T>` into a stack: the last element becomes the top.
+impl<T> From<Vec<T>> for VecStack<T> {
+    fn from(elements: Vec<T>) -> Self {
+        VecStack { elements }
+    }
+}
+
+/// Converts a stack into a `Vec<T>`: the top becomes the last element.
+impl<T> From<VecStack<T>> for Vec<T> {
+    fn from(stack: VecStack<T>) -> Self {
+        stack.elements
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecStack<T> {
+    type Item = &'a T;
+    type IntoIter = Rev<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecStack<T> {
+    type Item = &'a mut T;
+    type IntoIter = Rev<slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// [`VecStack`] prints its elements in the same format as
+/// [`crate::GenericStack`], e.g., `head->6->4->3->2.`
+impl<T: Display> Display for VecStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_head_list(f, self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display> Stack<T> for VecStack<T> {
+    fn new() -> Self {
+        VecStack::new()
+    }
+
+    fn push(&mut self, element: T) {
+        VecStack::push(self, element);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        VecStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        VecStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        VecStack::peek_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display> StackExt<T> for VecStack<T> {
+    fn len(&self) -> usize {
+        VecStack::len(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug + PartialEq + Clone + Display> CapacityStack<T> for VecStack<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        VecStack::with_capacity(capacity)
+    }
+
+    fn capacity(&self) -> usize {
+        VecStack::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        VecStack::reserve(self, additional);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GenericStack;
+
+    #[test]
+    fn basics() {
+        let mut stack = VecStack::new();
+        assert_eq!(stack.pop(), None);
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.len(), 3);
+        assert_eq!(stack.pop(), Some(3));
+        if let Some(value) = stack.peek_mut() {
+            *value = 42;
+        }
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), vec![42, 1]);
+        assert_eq!(stack.to_string(), "head->42->1.");
+        assert_eq!(Vec::from(stack), vec![1, 42]);
+    }
+
+    /// We apply the same pseudo-random sequence of operations to a
+    /// [`GenericStack`] and to a [`VecStack`] and compare the results.
+    #[test]
+    fn differential() {
+        let mut reference = VecStack::new();
+        let mut stack = GenericStack::new();
+
+        // a linear congruential generator is good enough to pick operations
+        let mut state: u64 = 42;
+        let mut random = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            state >> 33
+        };
+
+        let steps = if cfg!(miri) { 1_000 } else { 10_000 };
+        for step in 0..steps {
+            match random() % 4 {
+                0 | 1 => {
+                    reference.push(step);
+                    stack.push(step);
+                }
+                2 => assert_eq!(reference.pop(), stack.pop()),
+                _ => {
+                    if let (Some(a), Some(b)) = (reference.peek_mut(), stack.peek_mut()) {
+                        *a += 1;
+                        *b += 1;
+                    }
+                }
+            }
+            assert_eq!(reference.len(), stack.len());
+            assert_eq!(reference.peek(), stack.peek());
+        }
+        assert!(reference.iter().eq(stack.iter()));
+        assert_eq!(reference.to_string(), stack.to_string());
+    }
+}
@@ -0,0 +1,90 @@
+//! JavaScript bindings of the stack generated by `wasm-bindgen`.
+
+use crate::GenericStack;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+/// `JsStack` exports a [`GenericStack`] of JavaScript values to
+/// JavaScript, e.g., for demos in the browser. Build the module as a
+/// `cdylib` and generate the JavaScript glue code,
+///
+/// ```sh
+/// cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+/// wasm-bindgen --out-dir pkg target/wasm32-unknown-unknown/release/ll_stack.wasm
+/// ```
+///
+/// and use it like a JavaScript class:
+///
+/// ```js
+/// import { JsStack } from "./pkg/ll_stack.js";
+/// const stack = new JsStack();
+/// stack.push(1);
+/// stack.push("two");
+/// console.log(stack.length);    // 2
+/// console.log(stack.toArray()); // ["two", 1]
+/// console.log(stack.pop());     // "two"
+/// ```
+///
+/// The stack stores references to the values, i.e., it does not copy
+/// JavaScript objects. `pop` and `peek` return `undefined` if the stack is
+/// empty.
+#[wasm_bindgen]
+pub struct JsStack {
+    stack: GenericStack<JsValue>,
+}
+
+#[wasm_bindgen]
+impl JsStack {
+    /// Create a new, empty stack.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsStack {
+        JsStack {
+            stack: GenericStack::new(),
+        }
+    }
+
+    /// push a new value on the top of the stack.
+    pub fn push(&mut self, value: JsValue) {
+        self.stack.push(value);
+    }
+
+    /// Removes and returns the top value of the stack if it exists.
+    pub fn pop(&mut self) -> Option<JsValue> {
+        self.stack.pop()
+    }
+
+    /// Returns the top value of the stack if it exists.
+    pub fn peek(&self) -> Option<JsValue> {
+        self.stack.peek().cloned()
+    }
+
+    /// the number of values stored on the stack
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns `true` if the stack does not contain any values.
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns the values from the top to the bottom of the stack as an
+    /// array, like [`GenericStack::to_vec`].
+    #[wasm_bindgen(js_name = toArray)]
+    pub fn to_array(&self) -> Vec<JsValue> {
+        self.stack.to_vec()
+    }
+
+    /// Removes all values from the stack.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+impl Default for JsStack {
+    fn default() -> Self {
+        JsStack::new()
+    }
+}